@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+/// An additional way to launch the application. Such as "New Window".
+///
+/// Shown in the right-click menu of the launcher.
+/// Written as a `[Desktop Action <id>]` group on Linux. Ignored on Windows.
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::DesktopAction;
+/// let action = DesktopAction::new("new-window", "New Window")
+///     .arg("/usr/bin/my-program")
+///     .arg("--new-window");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DesktopAction {
+    /// Identifier of the action. Used in the group name.
+    ///
+    /// May only contain `A-Z`, `a-z`, `0-9` and `-`. Other ids fail to save as a `.desktop` file.
+    pub id: String,
+    /// Name of the action shown to the user.
+    pub name: String,
+    /// The executable followed by its arguments.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub exec: Vec<String>,
+    /// Icon of the action.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub icon: Option<PathBuf>,
+}
+impl DesktopAction {
+    /// Creates a new action with no command.
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+    /// Adds an argument to the command. The first argument is the executable.
+    pub fn arg(mut self, argument: impl Into<String>) -> Self {
+        self.exec.push(argument.into());
+        self
+    }
+    /// Sets the icon of the action.
+    pub fn icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
@@ -0,0 +1,131 @@
+use super::{FileShortcutError, ShortcutFile, ShortcutTarget};
+use std::path::PathBuf;
+
+/// Builds a [ShortcutFile] and checks the required fields are set.
+///
+/// The builder methods of [ShortcutFile] can be used through [ShortcutFileBuilder::with].
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::ShortcutFileBuilder;
+/// let shortcut = ShortcutFileBuilder::new()
+///     .name("My Program")
+///     .path("/usr/bin/my-program")
+///     .with(|shortcut| shortcut.category("Utility"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(shortcut.categories, vec!["Utility"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutFileBuilder {
+    target: Option<ShortcutTarget>,
+    shortcut: ShortcutFile,
+}
+impl ShortcutFileBuilder {
+    /// Creates a builder with no fields set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the name of the shortcut.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.shortcut.name = name.into();
+        self
+    }
+    /// Sets the path the shortcut points to.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.target = Some(ShortcutTarget::Path(path.into()));
+        self
+    }
+    /// Sets the URL the shortcut points to.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.target = Some(ShortcutTarget::Url(url.into()));
+        self
+    }
+    /// Sets the description of the shortcut.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.shortcut.description = Some(description.into());
+        self
+    }
+    /// Adds an argument to the shortcut.
+    pub fn arg(mut self, argument: impl Into<String>) -> Self {
+        self.shortcut.arguments.push(argument.into());
+        self
+    }
+    /// Sets the icon of the shortcut.
+    pub fn icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.shortcut.icon = Some(icon.into());
+        self
+    }
+    /// Sets the working directory of the shortcut.
+    pub fn working_directory(mut self, working_directory: impl Into<PathBuf>) -> Self {
+        self.shortcut.working_directory = Some(working_directory.into());
+        self
+    }
+    /// Applies any builder method of [ShortcutFile]
+    pub fn with(mut self, f: impl FnOnce(ShortcutFile) -> ShortcutFile) -> Self {
+        self.shortcut = f(self.shortcut);
+        self
+    }
+    /// Creates the shortcut.
+    ///
+    /// Fails with [FileShortcutError::MissingTarget] if no path or URL was set.
+    /// On Linux the name is required and fails with [FileShortcutError::EmptyName]
+    pub fn build(self) -> Result<ShortcutFile, FileShortcutError> {
+        let Self {
+            target,
+            mut shortcut,
+        } = self;
+        match target {
+            Some(ShortcutTarget::Path(path)) if path.as_os_str().is_empty() => {
+                return Err(FileShortcutError::MissingTarget)
+            }
+            Some(target) => shortcut.target = target,
+            None => return Err(FileShortcutError::MissingTarget),
+        }
+        if cfg!(target_os = "linux") && shortcut.name.trim().is_empty() {
+            return Err(FileShortcutError::EmptyName);
+        }
+        Ok(shortcut)
+    }
+}
+impl ShortcutFile {
+    /// Creates a [ShortcutFileBuilder]
+    pub fn builder() -> ShortcutFileBuilder {
+        ShortcutFileBuilder::new()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::ShortcutFileBuilder;
+    use crate::shortcut_files::{FileShortcutError, ShortcutFile};
+
+    #[test]
+    fn test_build() {
+        let shortcut = ShortcutFileBuilder::new()
+            .name("Test")
+            .path("/usr/bin/ls")
+            .arg("-l")
+            .with(|shortcut| shortcut.category("Utility"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            shortcut,
+            ShortcutFile::new("Test", "/usr/bin/ls")
+                .arg("-l")
+                .category("Utility")
+        );
+    }
+    #[test]
+    fn test_missing_target() {
+        let result = ShortcutFile::builder().name("Test").build();
+        assert!(matches!(result, Err(FileShortcutError::MissingTarget)));
+        let result = ShortcutFile::builder().name("Test").path("").build();
+        assert!(matches!(result, Err(FileShortcutError::MissingTarget)));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_missing_name() {
+        let result = ShortcutFile::builder().path("/usr/bin/ls").build();
+        assert!(matches!(result, Err(FileShortcutError::EmptyName)));
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+
+/// A main category of the freedesktop menu spec. Used by application menus to group launchers.
+///
+/// Use [ShortcutFile::category](super::ShortcutFile::category) for additional categories
+/// such as `TextEditor` or custom ones starting with `X-`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    AudioVideo,
+    Audio,
+    Video,
+    Development,
+    Education,
+    Game,
+    Graphics,
+    Network,
+    Office,
+    Science,
+    Settings,
+    System,
+    Utility,
+}
+impl Category {
+    /// Every main category.
+    pub const ALL: [Category; 13] = [
+        Category::AudioVideo,
+        Category::Audio,
+        Category::Video,
+        Category::Development,
+        Category::Education,
+        Category::Game,
+        Category::Graphics,
+        Category::Network,
+        Category::Office,
+        Category::Science,
+        Category::Settings,
+        Category::System,
+        Category::Utility,
+    ];
+    /// The value written to `Categories`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::AudioVideo => "AudioVideo",
+            Category::Audio => "Audio",
+            Category::Video => "Video",
+            Category::Development => "Development",
+            Category::Education => "Education",
+            Category::Game => "Game",
+            Category::Graphics => "Graphics",
+            Category::Network => "Network",
+            Category::Office => "Office",
+            Category::Science => "Science",
+            Category::Settings => "Settings",
+            Category::System => "System",
+            Category::Utility => "Utility",
+        }
+    }
+    /// Checks if the category is registered by the freedesktop menu spec or starts with `X-`
+    pub fn is_registered(category: &str) -> bool {
+        category.starts_with("X-")
+            || Category::ALL.iter().any(|v| v.as_str() == category)
+            || ADDITIONAL_CATEGORIES.contains(&category)
+            || RESERVED_CATEGORIES.contains(&category)
+    }
+}
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+/// Additional categories of the freedesktop menu spec.
+const ADDITIONAL_CATEGORIES: &[&str] = &[
+    "Building",
+    "Debugger",
+    "IDE",
+    "GUIDesigner",
+    "Profiling",
+    "RevisionControl",
+    "Translation",
+    "Calendar",
+    "ContactManagement",
+    "Database",
+    "Dictionary",
+    "Chart",
+    "Email",
+    "Finance",
+    "FlowChart",
+    "PDA",
+    "ProjectManagement",
+    "Presentation",
+    "Spreadsheet",
+    "WordProcessor",
+    "2DGraphics",
+    "VectorGraphics",
+    "RasterGraphics",
+    "3DGraphics",
+    "Scanning",
+    "OCR",
+    "Photography",
+    "Publishing",
+    "Viewer",
+    "TextTools",
+    "DesktopSettings",
+    "HardwareSettings",
+    "Printing",
+    "PackageManager",
+    "Dialup",
+    "InstantMessaging",
+    "Chat",
+    "IRCClient",
+    "Feed",
+    "FileTransfer",
+    "HamRadio",
+    "News",
+    "P2P",
+    "RemoteAccess",
+    "Telephony",
+    "TelephonyTools",
+    "VideoConference",
+    "WebBrowser",
+    "WebDevelopment",
+    "Midi",
+    "Mixer",
+    "Sequencer",
+    "Tuner",
+    "TV",
+    "AudioVideoEditing",
+    "Player",
+    "Recorder",
+    "DiscBurning",
+    "ActionGame",
+    "AdventureGame",
+    "ArcadeGame",
+    "BoardGame",
+    "BlocksGame",
+    "CardGame",
+    "KidsGame",
+    "LogicGame",
+    "RolePlaying",
+    "Shooter",
+    "Simulation",
+    "SportsGame",
+    "StrategyGame",
+    "Art",
+    "Construction",
+    "Music",
+    "Languages",
+    "ArtificialIntelligence",
+    "Astronomy",
+    "Biology",
+    "Chemistry",
+    "ComputerScience",
+    "DataVisualization",
+    "Economy",
+    "Electricity",
+    "Geography",
+    "Geology",
+    "Geoscience",
+    "History",
+    "Humanities",
+    "ImageProcessing",
+    "Literature",
+    "Maps",
+    "Math",
+    "NumericalAnalysis",
+    "MedicalSoftware",
+    "Physics",
+    "Robotics",
+    "Spirituality",
+    "Sports",
+    "ParallelComputing",
+    "Amusement",
+    "Archiving",
+    "Compression",
+    "Electronics",
+    "Emulator",
+    "Engineering",
+    "FileTools",
+    "FileManager",
+    "TerminalEmulator",
+    "Filesystem",
+    "Monitor",
+    "Security",
+    "Accessibility",
+    "Calculator",
+    "Clock",
+    "TextEditor",
+    "Documentation",
+    "Adult",
+    "Core",
+    "KDE",
+    "GNOME",
+    "XFCE",
+    "DDE",
+    "GTK",
+    "Qt",
+    "Motif",
+    "Java",
+    "ConsoleOnly",
+];
+/// Reserved categories of the freedesktop menu spec. Only used with `OnlyShowIn`.
+const RESERVED_CATEGORIES: &[&str] = &["Screensaver", "TrayIcon", "Applet", "Shell"];
+#[cfg(test)]
+mod tests {
+    use super::Category;
+
+    #[test]
+    fn test_is_registered() {
+        for category in Category::ALL {
+            assert!(Category::is_registered(category.as_str()));
+        }
+        assert!(Category::is_registered("TextEditor"));
+        assert!(Category::is_registered("X-Custom"));
+        assert!(!Category::is_registered("Utilites"));
+    }
+}
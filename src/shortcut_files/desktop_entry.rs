@@ -0,0 +1,191 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A line of a desktop file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Line {
+    /// A group header such as `[Desktop Entry]`. Stores the name without the brackets.
+    Group(String),
+    /// A `Key=Value` pair.
+    Entry { key: String, value: String },
+    /// Comments, blank lines and anything else. Kept as is.
+    Other(String),
+}
+/// A desktop file that keeps the order of its keys, comments and blank lines.
+///
+/// Use this to edit an existing desktop file without rewriting the whole file.
+/// [ShortcutFile](super::ShortcutFile) only keeps the values it understands.
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::DesktopEntry;
+/// let mut entry: DesktopEntry = "[Desktop Entry]\n# My App\nName=Old\n".parse().unwrap();
+/// entry.set("Desktop Entry", "Name", "New");
+/// assert_eq!(entry.to_string(), "[Desktop Entry]\n# My App\nName=New\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DesktopEntry {
+    lines: Vec<Line>,
+}
+impl DesktopEntry {
+    /// The main group of a desktop file.
+    pub const MAIN_GROUP: &'static str = "Desktop Entry";
+
+    /// Returns the value of the key in the group.
+    pub fn get(&self, group: &str, key: &str) -> Option<&str> {
+        match &self.lines[self.find(group, key)?] {
+            Line::Entry { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+    /// Returns the keys and values of the group in the order they appear.
+    pub fn entries<'a>(&'a self, group: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        let mut in_group = false;
+        self.lines.iter().filter_map(move |line| match line {
+            Line::Group(name) => {
+                in_group = name == group;
+                None
+            }
+            Line::Entry { key, value } if in_group => Some((key.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+    /// Returns the names of the groups in the order they appear.
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Group(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+    /// Sets the value of the key in the group.
+    ///
+    /// An existing key is updated in place.
+    /// Otherwise the key is added after the last key of the group. The group is created if missing.
+    pub fn set(&mut self, group: &str, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self.find(group, &key) {
+            Some(index) => self.lines[index] = Line::Entry { key, value },
+            None => {
+                let index = match self.group_end(group) {
+                    Some(index) => index,
+                    None => {
+                        self.lines.push(Line::Group(group.to_string()));
+                        self.lines.len()
+                    }
+                };
+                self.lines.insert(index, Line::Entry { key, value });
+            }
+        }
+    }
+    /// Removes the key from the group. Returns the old value.
+    pub fn remove(&mut self, group: &str, key: &str) -> Option<String> {
+        let index = self.find(group, key)?;
+        match self.lines.remove(index) {
+            Line::Entry { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+    /// Index of the line containing the key in the group.
+    fn find(&self, group: &str, key: &str) -> Option<usize> {
+        let mut in_group = false;
+        self.lines.iter().position(|line| match line {
+            Line::Group(name) => {
+                in_group = name == group;
+                false
+            }
+            Line::Entry { key: entry_key, .. } => in_group && entry_key == key,
+            Line::Other(_) => false,
+        })
+    }
+    /// Index after the last key of the group. `None` if the group does not exist.
+    fn group_end(&self, group: &str) -> Option<usize> {
+        let mut in_group = false;
+        let mut end = None;
+        for (index, line) in self.lines.iter().enumerate() {
+            match line {
+                Line::Group(name) => {
+                    in_group = name == group;
+                    if in_group {
+                        end = Some(index + 1);
+                    }
+                }
+                Line::Entry { .. } if in_group => end = Some(index + 1),
+                _ => {}
+            }
+        }
+        end
+    }
+}
+impl FromStr for DesktopEntry {
+    type Err = std::convert::Infallible;
+
+    /// Parses a desktop file. Lines that are not understood are kept as is.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim();
+                if let Some(group) = trimmed.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    return Line::Group(group.to_string());
+                }
+                if trimmed.starts_with('#') {
+                    return Line::Other(line.to_string());
+                }
+                match trimmed.split_once('=') {
+                    Some((key, value)) => Line::Entry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    },
+                    None => Line::Other(line.to_string()),
+                }
+            })
+            .collect();
+        Ok(Self { lines })
+    }
+}
+impl Display for DesktopEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Group(name) => writeln!(f, "[{}]", name)?,
+                Line::Entry { key, value } => writeln!(f, "{}={}", key, value)?,
+                Line::Other(line) => writeln!(f, "{}", line)?,
+            }
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::DesktopEntry;
+
+    #[test]
+    fn test_preserve_comments_and_order() {
+        let content = "# Created by hand\n[Desktop Entry]\nType=Application\n# The name\nName=Old\nExec=/usr/bin/ls\n\n[Desktop Action new]\nName=New Window\n";
+        let mut entry: DesktopEntry = content.parse().unwrap();
+        entry.set(DesktopEntry::MAIN_GROUP, "Name", "New");
+        assert_eq!(entry.to_string(), content.replace("Name=Old", "Name=New"));
+        assert_eq!(entry.get("Desktop Action new", "Name"), Some("New Window"));
+    }
+    #[test]
+    fn test_set_and_remove() {
+        let mut entry: DesktopEntry =
+            "[Desktop Entry]\nName=Test\n\n[Desktop Action new]\nName=New\n"
+                .parse()
+                .unwrap();
+        entry.set(DesktopEntry::MAIN_GROUP, "Comment", "A comment");
+        entry.set("Other Group", "Key", "Value");
+        assert_eq!(
+            entry.remove(DesktopEntry::MAIN_GROUP, "Name"),
+            Some("Test".to_string())
+        );
+        assert_eq!(
+            entry.to_string(),
+            "[Desktop Entry]\nComment=A comment\n\n[Desktop Action new]\nName=New\n[Other Group]\nKey=Value\n"
+        );
+        assert_eq!(
+            entry.groups().collect::<Vec<_>>(),
+            vec!["Desktop Entry", "Desktop Action new", "Other Group"]
+        );
+    }
+}
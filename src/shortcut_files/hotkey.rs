@@ -0,0 +1,178 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HotkeyParseError {
+    #[error("Hotkey is missing a key")]
+    MissingKey,
+    #[error("Unknown key or modifier: {0}")]
+    UnknownKey(String),
+}
+
+/// A key that can be used in a [Hotkey].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HotkeyKey {
+    /// A letter or digit. Letters are always stored uppercase.
+    Char(char),
+    /// A function key. `F1` through `F24`.
+    Function(u8),
+}
+impl Display for HotkeyKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyKey::Char(c) => write!(f, "{}", c),
+            HotkeyKey::Function(n) => write!(f, "F{}", n),
+        }
+    }
+}
+impl FromStr for HotkeyKey {
+    type Err = HotkeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() => {
+                return Ok(HotkeyKey::Char(c.to_ascii_uppercase()));
+            }
+            (Some('F' | 'f'), Some(_)) => {
+                if let Ok(n @ 1..=24) = s[1..].parse::<u8>() {
+                    return Ok(HotkeyKey::Function(n));
+                }
+            }
+            _ => {}
+        }
+        Err(HotkeyParseError::UnknownKey(s.to_owned()))
+    }
+}
+/// Modifier keys that must be held for a [Hotkey].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HotkeyModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Windows or Super key.
+    ///
+    /// Not supported by Windows shortcuts.
+    pub win: bool,
+}
+/// A global keyboard shortcut for launching the shortcut.
+///
+/// On Windows this is stored in the `.lnk` file.
+/// On Linux the desktop entry spec has no hotkey key so it is written as `X-KDE-Shortcuts=`.
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::{Hotkey, HotkeyKey};
+/// let hotkey = Hotkey::new(HotkeyKey::Char('P')).ctrl().alt();
+/// assert_eq!(hotkey.to_string(), "Ctrl+Alt+P");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub modifiers: HotkeyModifiers,
+    pub key: HotkeyKey,
+}
+impl Hotkey {
+    /// Creates a new hotkey with no modifiers.
+    pub fn new(key: HotkeyKey) -> Self {
+        Self {
+            modifiers: HotkeyModifiers::default(),
+            key,
+        }
+    }
+    /// Requires the Ctrl key.
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+    /// Requires the Alt key.
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+    /// Requires the Shift key.
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+    /// Requires the Windows or Super key.
+    pub fn win(mut self) -> Self {
+        self.modifiers.win = true;
+        self
+    }
+}
+/// Formats the hotkey the way KDE does. `Ctrl+Alt+Shift+Meta+P`
+impl Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let HotkeyModifiers {
+            ctrl,
+            alt,
+            shift,
+            win,
+        } = self.modifiers;
+        if ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if alt {
+            write!(f, "Alt+")?;
+        }
+        if shift {
+            write!(f, "Shift+")?;
+        }
+        if win {
+            write!(f, "Meta+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+impl FromStr for Hotkey {
+    type Err = HotkeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = HotkeyModifiers::default();
+        let mut key = None;
+        for part in s.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "meta" | "win" | "super" => modifiers.win = true,
+                _ => key = Some(part.parse()?),
+            }
+        }
+        Ok(Self {
+            modifiers,
+            key: key.ok_or(HotkeyParseError::MissingKey)?,
+        })
+    }
+}
+/// Serialized as the string form. `Ctrl+Alt+P`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Hotkey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Hotkey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::{Hotkey, HotkeyKey, HotkeyParseError};
+
+    #[test]
+    fn test_parse_hotkey() {
+        let hotkey = Hotkey::new(HotkeyKey::Char('P')).ctrl().alt();
+        assert_eq!(hotkey.to_string().parse::<Hotkey>().unwrap(), hotkey);
+        let hotkey = Hotkey::new(HotkeyKey::Function(12)).shift().win();
+        assert_eq!("Shift+Meta+F12".parse::<Hotkey>().unwrap(), hotkey);
+        assert_eq!(
+            "Ctrl+Alt".parse::<Hotkey>(),
+            Err(HotkeyParseError::MissingKey)
+        );
+    }
+}
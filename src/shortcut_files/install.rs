@@ -0,0 +1,177 @@
+//! Saving shortcuts into the standard directories of the desktop.
+#[cfg(target_os = "linux")]
+use super::{home_dir, sanitize_file_name};
+use super::{ErrorType, FileShortcutError, ShortcutFile};
+use std::path::PathBuf;
+
+/// Key that enables an autostart entry in GNOME.
+#[cfg(target_os = "linux")]
+const AUTOSTART_ENABLED_KEY: &str = "X-GNOME-Autostart-enabled";
+
+impl ShortcutFile {
+    /// Saves the shortcut into the XDG autostart directory so it is launched on login.
+    ///
+    /// The directory is `$XDG_CONFIG_HOME/autostart`. Defaulting to `~/.config/autostart`.
+    /// The file name is the app id or derived from the name. See [ShortcutFile::save_to_dir]
+    #[cfg(target_os = "linux")]
+    pub fn install_autostart(self) -> Result<PathBuf, FileShortcutError> {
+        self.extra(AUTOSTART_ENABLED_KEY, "true")
+            .install_to(autostart_dir()?)
+    }
+    /// Saves the shortcut into the applications menu of the current user.
+    ///
+    /// On Linux the directory is `$XDG_DATA_HOME/applications`. Defaulting to `~/.local/share/applications`.
+    ///
+    /// On Windows the shortcut is saved in the Programs folder of the Start Menu.
+    ///
+    /// The file name is the app id or derived from the name. See [ShortcutFile::save_to_dir]
+    pub fn install_menu_entry(self) -> Result<PathBuf, FileShortcutError> {
+        self.install_to(menu_dir()?)
+    }
+    /// Saves the shortcut onto the Desktop of the current user.
+    ///
+    /// The file name is derived from the name. See [ShortcutFile::save_to_dir]
+    #[cfg(target_os = "windows")]
+    pub fn install_to_desktop(self) -> Result<PathBuf, FileShortcutError> {
+        use windows::Win32::UI::Shell::FOLDERID_Desktop;
+        self.save_to_dir(super::windows::known_folder(&FOLDERID_Desktop)?)
+    }
+    /// Saves the shortcut into the Programs folder of the Start Menu of the current user.
+    ///
+    /// Same as [ShortcutFile::install_menu_entry]
+    #[cfg(target_os = "windows")]
+    pub fn install_to_start_menu(self) -> Result<PathBuf, FileShortcutError> {
+        self.install_menu_entry()
+    }
+    /// Creates the directory and saves the shortcut into it.
+    ///
+    /// On Linux the file is named after the app id if one is set.
+    fn install_to(self, dir: PathBuf) -> Result<PathBuf, FileShortcutError> {
+        std::fs::create_dir_all(&dir).map_err(ErrorType::from)?;
+        #[cfg(target_os = "linux")]
+        if let Some(app_id) = &self.app_id {
+            let file_name = sanitize_file_name(app_id);
+            if !file_name.is_empty() {
+                let to = dir.join(format!("{}.desktop", file_name));
+                return self.save(to);
+            }
+        }
+        self.save_to_dir(dir)
+    }
+}
+/// Removes a shortcut saved with [ShortcutFile::install_autostart]
+///
+/// Pass the app id instead of the name if one was set.
+/// Does nothing if the shortcut is not installed.
+#[cfg(target_os = "linux")]
+pub fn uninstall_autostart(name: &str) -> Result<(), FileShortcutError> {
+    let path = autostart_dir()?.join(format!("{}.desktop", sanitize_file_name(name)));
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(ErrorType::from(err).into()),
+    }
+}
+/// `$XDG_CONFIG_HOME/autostart`
+#[cfg(target_os = "linux")]
+fn autostart_dir() -> Result<PathBuf, FileShortcutError> {
+    Ok(xdg_dir("XDG_CONFIG_HOME", ".config")?.join("autostart"))
+}
+/// `$XDG_DATA_HOME/applications`
+#[cfg(target_os = "linux")]
+fn menu_dir() -> Result<PathBuf, FileShortcutError> {
+    Ok(xdg_dir("XDG_DATA_HOME", ".local/share")?.join("applications"))
+}
+/// The Programs folder of the Start Menu.
+#[cfg(target_os = "windows")]
+fn menu_dir() -> Result<PathBuf, FileShortcutError> {
+    use windows::Win32::UI::Shell::FOLDERID_Programs;
+    Ok(super::windows::known_folder(&FOLDERID_Programs)?)
+}
+/// Reads an XDG base directory from the environment. Falling back to the given directory in home.
+#[cfg(target_os = "linux")]
+fn xdg_dir(key: &str, default: &str) -> Result<PathBuf, FileShortcutError> {
+    if let Some(dir) = std::env::var_os(key).filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    home_dir()
+        .map(|home| home.join(default))
+        .ok_or(FileShortcutError::UnknownDirectory(key.to_string()))
+}
+#[cfg(test)]
+mod tests {
+    /// Held by tests that change the environment so they do not run at the same time.
+    #[cfg(target_os = "linux")]
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_install_autostart() {
+        use super::uninstall_autostart;
+        use crate::shortcut_files::ShortcutFile;
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let config_home = std::env::temp_dir().join("test_install_autostart");
+        std::env::set_var("XDG_CONFIG_HOME", &config_home);
+        let target = std::env::current_exe().unwrap();
+        let path = ShortcutFile::new("My App", target)
+            .install_autostart()
+            .unwrap();
+        assert_eq!(path, config_home.join("autostart/My App.desktop"));
+        let shortcut = ShortcutFile::read(&path).unwrap();
+        assert_eq!(
+            shortcut
+                .extra
+                .get("X-GNOME-Autostart-enabled")
+                .map(String::as_str),
+            Some("true")
+        );
+        uninstall_autostart("My App").unwrap();
+        assert!(!path.exists());
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_install_menu_entry() {
+        use crate::shortcut_files::ShortcutFile;
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let data_home = std::env::temp_dir().join("test_install_menu_entry");
+        std::env::set_var("XDG_DATA_HOME", &data_home);
+        let target = std::env::current_exe().unwrap();
+        let path = ShortcutFile::new("Menu/App", target)
+            .install_menu_entry()
+            .unwrap();
+        assert_eq!(path, data_home.join("applications/Menu_App.desktop"));
+        assert!(path.exists());
+    }
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_install_to_desktop() {
+        use crate::shortcut_files::ShortcutFile;
+        use windows::Win32::UI::Shell::FOLDERID_Desktop;
+        let desktop = crate::shortcut_files::windows::known_folder(&FOLDERID_Desktop).unwrap();
+        assert!(desktop.is_dir());
+        let target = std::env::current_exe().unwrap();
+        let path = ShortcutFile::new("test_install_to_desktop", target)
+            .install_to_desktop()
+            .unwrap();
+        assert_eq!(path, desktop.join("test_install_to_desktop.lnk"));
+        assert!(path.exists());
+        std::fs::remove_file(path).unwrap();
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_install_menu_entry_app_id() {
+        use crate::shortcut_files::ShortcutFile;
+        let dir = std::env::temp_dir().join("test_install_menu_entry_app_id/applications");
+        let target = std::env::current_exe().unwrap();
+        let path = ShortcutFile::new("My App", target)
+            .app_id("com.example.MyApp")
+            .install_to(dir.clone())
+            .unwrap();
+        assert_eq!(path, dir.join("com.example.MyApp.desktop"));
+        let shortcut = ShortcutFile::read(&path).unwrap();
+        assert_eq!(
+            shortcut.startup_wm_class.as_deref(),
+            Some("com.example.MyApp")
+        );
+    }
+}
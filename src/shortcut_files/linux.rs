@@ -1,7 +1,24 @@
-use super::ShortcutFile;
+//! Reading and writing Freedesktop Desktop Entry (`.desktop`) files.
+//!
+//! Available on Linux and on every other host with the `desktop` feature.
+//! Unlike [ShortcutFile::save](super::ShortcutFile::save) these functions never pick a format from the host.
+//!
+//! ```
+//! use shortcut_rs::shortcut_files::{linux::render_desktop_entry, ShortcutFile};
+//! let content = render_desktop_entry(ShortcutFile::new("Test", "/usr/bin/ls")).unwrap();
+//! assert!(content.contains("Exec=/usr/bin/ls"));
+//! ```
+use super::temporary_path;
+use super::url::{decode_url, encode_url};
+use super::{
+    DesktopAction, DuplicateKeys, EntryType, LineEnding, ParseOptions, ShortcutFile,
+    ShortcutTarget, WriteOptions, DESKTOP_SPEC_VERSION,
+};
 use std::{
-    fs::OpenOptions,
-    io::Write,
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{BufWriter, Read, Write},
     path::{Path, PathBuf},
 };
 
@@ -11,39 +28,236 @@ use thiserror::Error;
 pub enum LinuxShortcutError {
     #[error(transparent)]
     IOErr(#[from] std::io::Error),
+    /// Reading or writing the file failed. Contains the path of the file.
+    #[error("{path:?}: {source}")]
+    IoAt {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     #[error("Path was not valid UTF-8")]
     PathNotValidUTF8,
     #[error("Missing Value: {0}")]
     MissingValue(&'static str),
+    /// A line could not be parsed. The line number starts at 1.
+    #[error("Line {line}: {message}")]
+    ParseError { line: usize, message: String },
+    #[error("File does not start with a [Desktop Entry] group")]
+    NotADesktopEntry,
+    #[error("Shortcut target {0} is not supported by .desktop files")]
+    UnsupportedTarget(ShortcutTarget),
+    #[error("Key {0} appears more than once in the same group")]
+    DuplicateKey(String),
+    /// The id of a [DesktopAction] contains characters other than `A-Z`, `a-z`, `0-9` and `-`.
+    #[error("Invalid action id {0:?}")]
+    InvalidActionId(String),
+    /// A key of [ShortcutFile::extra] is not a valid key or is written from another field. Such as `Name`
+    #[error("Invalid extra key {0:?}")]
+    InvalidExtraKey(String),
+}
+impl LinuxShortcutError {
+    /// Adds the path of the file to an IO error.
+    fn at(self, path: &Path) -> Self {
+        match self {
+            LinuxShortcutError::IOErr(source) => LinuxShortcutError::IoAt {
+                path: path.to_path_buf(),
+                source,
+            },
+            err => err,
+        }
+    }
 }
 
+/// Saves a Shortcut as a Desktop Entry.
+///
+/// On Unix the file is marked as executable.
+/// The file is written next to the destination and renamed over it so a failed save leaves the old file intact.
 pub fn save_shortcut_file(
     shortcut: ShortcutFile,
     to: impl AsRef<Path>,
+) -> Result<(), LinuxShortcutError> {
+    save_shortcut_file_with_options(shortcut, to, WriteOptions::default())
+}
+/// Same as [save_shortcut_file] but with options for how the file is written.
+pub fn save_shortcut_file_with_options(
+    shortcut: ShortcutFile,
+    to: impl AsRef<Path>,
+    options: WriteOptions,
 ) -> Result<(), LinuxShortcutError> {
     debug!(
-        "Creating Shortcut to {:?} at {:?}",
-        shortcut.path,
+        "Creating Shortcut to {} at {:?}",
+        shortcut.target,
         to.as_ref()
     );
+    let temporary = temporary_path(to.as_ref());
+    if let Err(err) = write_desktop_file(shortcut, &temporary, options) {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(err.at(to.as_ref()));
+    }
+    std::fs::rename(&temporary, to.as_ref()).map_err(|err| {
+        let _ = std::fs::remove_file(&temporary);
+        LinuxShortcutError::from(err).at(to.as_ref())
+    })
+}
+/// Writes the Desktop Entry to a new file and marks it as executable.
+fn write_desktop_file(
+    shortcut: ShortcutFile,
+    path: &Path,
+    options: WriteOptions,
+) -> Result<(), LinuxShortcutError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_desktop_entry_with_options(shortcut, &mut writer, options)?;
+    writer.flush()?;
+    // Desktop environments treat non executable desktop files as untrusted.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+/// Async version of [save_shortcut_file] using `tokio::fs`
+#[cfg(feature = "tokio")]
+pub async fn save_shortcut_file_async(
+    shortcut: ShortcutFile,
+    to: impl AsRef<Path>,
+) -> Result<(), LinuxShortcutError> {
+    debug!(
+        "Creating Shortcut to {} at {:?}",
+        shortcut.target,
+        to.as_ref()
+    );
+    let content = render_desktop_entry(shortcut)?;
+    let temporary = temporary_path(to.as_ref());
+    let written = async {
+        tokio::fs::write(&temporary, content).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&temporary, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+        Ok::<(), std::io::Error>(())
+    };
+    if let Err(err) = written.await {
+        let _ = tokio::fs::remove_file(&temporary).await;
+        return Err(LinuxShortcutError::from(err).at(to.as_ref()));
+    }
+    if let Err(err) = tokio::fs::rename(&temporary, to.as_ref()).await {
+        let _ = tokio::fs::remove_file(&temporary).await;
+        return Err(LinuxShortcutError::from(err).at(to.as_ref()));
+    }
+    Ok(())
+}
+/// Renders the content of a Desktop Entry without writing it.
+pub fn render_desktop_entry(shortcut: ShortcutFile) -> Result<String, LinuxShortcutError> {
+    let mut buffer = Vec::new();
+    write_desktop_entry(shortcut, &mut buffer)?;
+    // Paths are checked to be UTF-8 and every other value is a String so this never fails.
+    Ok(String::from_utf8(buffer).expect("every value written is UTF-8"))
+}
+/// Writes the content of a Desktop Entry to any writer.
+pub fn write_desktop_entry(
+    shortcut: ShortcutFile,
+    writer: impl Write,
+) -> Result<(), LinuxShortcutError> {
+    write_desktop_entry_with_options(shortcut, writer, WriteOptions::default())
+}
+/// Same as [write_desktop_entry] but with options for how the file is written.
+pub fn write_desktop_entry_with_options(
+    shortcut: ShortcutFile,
+    writer: impl Write,
+    options: WriteOptions,
+) -> Result<(), LinuxShortcutError> {
+    let mut writer = LineEndingWriter {
+        inner: writer,
+        line_ending: options.line_ending,
+    };
     let ShortcutFile {
         name,
-        path,
+        target,
         icon,
         description,
         arguments,
         working_directory,
         show_terminal,
         categories,
+        hotkey,
+        icon_index: _,
+        run_as_admin: _,
+        window_state: _,
+        startup_wm_class,
+        mime_types,
+        keywords,
+        localized_names,
+        localized_descriptions,
+        generic_name,
+        no_display,
+        hidden,
+        only_show_in,
+        not_show_in,
+        env,
+        extra,
+        actions,
+        desktop_spec_version,
+        startup_notify,
+        dbus_activatable,
+        single_main_window,
+        prefers_non_default_gpu,
+        try_exec,
+        exec_prefix,
+        expand_paths: _,
+        entry_type,
+        app_id,
+        implements,
+        field_codes,
+        tooltip: _,
     } = shortcut;
-    let file = OpenOptions::new().write(true).create(true).open(to)?;
-    let mut writer = std::io::BufWriter::new(file);
-    let command = path.to_str().ok_or(LinuxShortcutError::PathNotValidUTF8)?;
-    let exec = if !arguments.is_empty() {
-        let args = arguments.join(" ");
-        format!("Exec={} {}", command, args)
-    } else {
-        format!("Exec={}", command)
+    let entry_type = entry_type.unwrap_or(match target {
+        ShortcutTarget::Url(_) => EntryType::Link,
+        _ => EntryType::Application,
+    });
+    let exec = match (&target, entry_type) {
+        (_, EntryType::Directory) => None,
+        (ShortcutTarget::Path(path), _) => {
+            let command = path.to_str().ok_or(LinuxShortcutError::PathNotValidUTF8)?;
+            // Field codes at the end are written as is so they stay after every argument.
+            let codes_start = arguments
+                .iter()
+                .rposition(|v| !EXEC_FIELD_CODES.contains(&v.as_str()))
+                .map_or(0, |i| i + 1);
+            let (arguments, trailing_codes) = arguments.split_at(codes_start);
+            let mut codes: Vec<(usize, &str)> = trailing_codes
+                .iter()
+                .map(|v| (arguments.len(), v.as_str()))
+                .collect();
+            for (position, code) in &field_codes {
+                if !codes.iter().any(|(_, v)| v == code) {
+                    codes.push(((*position).min(arguments.len()), code));
+                }
+            }
+            let exec = if env.is_empty() {
+                compose_exec(command, arguments, &codes)
+            } else {
+                // The variables and the command come before the arguments.
+                for (position, _) in &mut codes {
+                    *position += env.len() + 1;
+                }
+                let mut env_arguments: Vec<String> = env
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                env_arguments.push(command.to_owned());
+                env_arguments.extend_from_slice(arguments);
+                compose_exec("env", &env_arguments, &codes)
+            };
+            // The quoted value is escaped again like every other string value.
+            let exec = match &exec_prefix {
+                Some(prefix) => format!("{} {}", prefix, exec),
+                None => exec,
+            };
+            Some(format!("Exec={}", escape_string(&exec)))
+        }
+        (ShortcutTarget::Url(url), _) => Some(format!("URL={}", encode_url(url))),
+        (target, _) => return Err(LinuxShortcutError::UnsupportedTarget(target.clone())),
     };
     let working_directory = working_directory
         .map(|v| {
@@ -59,22 +273,52 @@ pub fn save_shortcut_file(
                 .ok_or(LinuxShortcutError::PathNotValidUTF8)
         })
         .transpose()?;
-    let description = description.map(|v| format!("Comment={}", v));
-    let show_terminal = if show_terminal {
-        "Terminal=true"
-    } else {
-        "Terminal=false"
-    };
-    let categories = if !categories.is_empty() {
-        let categories = categories.join(";");
-        Some(format!("Categories={};", categories))
-    } else {
-        None
+    if let Some(action) = actions.iter().find(|v| !is_valid_action_id(&v.id)) {
+        return Err(LinuxShortcutError::InvalidActionId(action.id.clone()));
+    }
+    if let Some(key) = extra
+        .keys()
+        .find(|v| !is_valid_extra_key(v, hotkey.is_some()))
+    {
+        return Err(LinuxShortcutError::InvalidExtraKey(key.clone()));
+    }
+    let description = description.map(|v| format!("Comment={}", escape_string(&v)));
+    // Terminal is only valid for Type=Application
+    let show_terminal = match entry_type {
+        EntryType::Application if show_terminal => Some("Terminal=true"),
+        EntryType::Application if !options.omit_defaults => Some("Terminal=false"),
+        _ => None,
     };
-    writeln!(writer, "[Desktop Entry]")?;
-    writeln!(writer, "Type=Application")?;
-    writeln!(writer, "Name={}", name)?;
-    writeln!(writer, "{}", exec)?;
+    writeln!(
+        writer,
+        "[{}]",
+        options.group_name.as_deref().unwrap_or("Desktop Entry")
+    )?;
+    match desktop_spec_version {
+        Some(version) => writeln!(writer, "Version={}", version)?,
+        None if options.omit_defaults => {}
+        None => writeln!(writer, "Version={}", DESKTOP_SPEC_VERSION)?,
+    }
+    writeln!(writer, "Type={}", entry_type)?;
+    writeln!(writer, "Name={}", escape_string(&name))?;
+    for (locale, name) in localized_names {
+        writeln!(writer, "Name[{}]={}", locale, escape_string(&name))?;
+    }
+    if let Some(generic_name) = generic_name {
+        writeln!(writer, "GenericName={}", escape_string(&generic_name))?;
+    }
+    if let Some(exec) = exec {
+        writeln!(writer, "{}", exec)?;
+        if let Some(prefix) = &exec_prefix {
+            writeln!(writer, "{}={}", EXEC_PREFIX_KEY, escape_string(prefix))?;
+        }
+    }
+    if let Some(try_exec) = try_exec {
+        let try_exec = try_exec
+            .to_str()
+            .ok_or(LinuxShortcutError::PathNotValidUTF8)?;
+        writeln!(writer, "TryExec={}", try_exec)?;
+    }
     if let Some(working_directory) = working_directory {
         writeln!(writer, "{}", working_directory)?;
     }
@@ -84,37 +328,279 @@ pub fn save_shortcut_file(
     if let Some(description) = description {
         writeln!(writer, "{}", description)?;
     }
-    writeln!(writer, "{}", show_terminal)?;
-    if let Some(categories) = categories {
-        writeln!(writer, "{}", categories)?;
+    for (locale, description) in localized_descriptions {
+        writeln!(
+            writer,
+            "Comment[{}]={}",
+            locale,
+            escape_string(&description)
+        )?;
+    }
+    if let Some(show_terminal) = show_terminal {
+        writeln!(writer, "{}", show_terminal)?;
+    }
+    if no_display {
+        writeln!(writer, "NoDisplay=true")?;
+    }
+    if hidden {
+        writeln!(writer, "Hidden=true")?;
+    }
+    if !only_show_in.is_empty() {
+        writeln!(
+            writer,
+            "OnlyShowIn={}",
+            format_list(&only_show_in, &options)
+        )?;
+    }
+    if !not_show_in.is_empty() {
+        writeln!(writer, "NotShowIn={}", format_list(&not_show_in, &options))?;
+    }
+    if !categories.is_empty() {
+        writeln!(writer, "Categories={}", format_list(&categories, &options))?;
+    }
+    if !mime_types.is_empty() {
+        writeln!(writer, "MimeType={}", format_list(&mime_types, &options))?;
+    }
+    if !keywords.is_empty() {
+        writeln!(writer, "Keywords={}", format_list(&keywords, &options))?;
+    }
+    if !implements.is_empty() {
+        writeln!(writer, "Implements={}", format_list(&implements, &options))?;
+    }
+    if !actions.is_empty() {
+        let ids: Vec<String> = actions.iter().map(|v| v.id.clone()).collect();
+        writeln!(writer, "Actions={}", format_list(&ids, &options))?;
+    }
+    if let Some(startup_notify) = startup_notify {
+        writeln!(writer, "StartupNotify={}", startup_notify)?;
+    }
+    if let Some(dbus_activatable) = dbus_activatable {
+        writeln!(writer, "DBusActivatable={}", dbus_activatable)?;
+    }
+    if let Some(single_main_window) = single_main_window {
+        writeln!(writer, "SingleMainWindow={}", single_main_window)?;
+    }
+    if let Some(prefers_non_default_gpu) = prefers_non_default_gpu {
+        writeln!(writer, "PrefersNonDefaultGPU={}", prefers_non_default_gpu)?;
+    }
+    if let Some(startup_wm_class) = startup_wm_class.or(app_id) {
+        writeln!(writer, "StartupWMClass={}", startup_wm_class)?;
+    }
+    if let Some(hotkey) = hotkey {
+        writeln!(writer, "X-KDE-Shortcuts={}", hotkey)?;
+    }
+    for (key, value) in extra {
+        writeln!(writer, "{}={}", key, escape_string(&value))?;
+    }
+    for action in actions {
+        writeln!(writer)?;
+        writeln!(writer, "[Desktop Action {}]", action.id)?;
+        writeln!(writer, "Name={}", escape_string(&action.name))?;
+        if let Some((command, arguments)) = action.exec.split_first() {
+            let exec = compose_exec(command, arguments, &[]);
+            writeln!(writer, "Exec={}", escape_string(&exec))?;
+        }
+        if let Some(icon) = action.icon {
+            let icon = icon.to_str().ok_or(LinuxShortcutError::PathNotValidUTF8)?;
+            writeln!(writer, "Icon={}", icon)?;
+        }
     }
     writer.flush()?;
     Ok(())
 }
+/// Replaces every `\n` written with the chosen [LineEnding]
+struct LineEndingWriter<W> {
+    inner: W,
+    line_ending: LineEnding,
+}
+impl<W: Write> Write for LineEndingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.line_ending == LineEnding::Lf {
+            return self.inner.write(buf);
+        }
+        let mut lines = buf.split(|v| *v == b'\n');
+        if let Some(first) = lines.next() {
+            self.inner.write_all(first)?;
+        }
+        for line in lines {
+            self.inner.write_all(b"\r\n")?;
+            self.inner.write_all(line)?;
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
 pub fn read_shortcut_file(path: impl AsRef<Path>) -> Result<ShortcutFile, LinuxShortcutError> {
-    let read = std::fs::read_to_string(path)?;
+    read_shortcut_file_with_options(path, ParseOptions::default())
+}
+pub fn read_shortcut_file_with_options(
+    path: impl AsRef<Path>,
+    options: ParseOptions,
+) -> Result<ShortcutFile, LinuxShortcutError> {
+    let path = path.as_ref();
+    File::open(path)
+        .map_err(LinuxShortcutError::from)
+        .and_then(|file| read_desktop_entry(file, options))
+        .map_err(|err| err.at(path))
+}
+/// Reads a Desktop Entry from any reader.
+pub fn read_desktop_entry(
+    mut reader: impl Read,
+    options: ParseOptions,
+) -> Result<ShortcutFile, LinuxShortcutError> {
+    let mut read = String::new();
+    reader.read_to_string(&mut read)?;
+    parse_desktop_entry(&read, options)
+}
+/// Async version of [read_shortcut_file] using `tokio::fs`
+#[cfg(feature = "tokio")]
+pub async fn read_shortcut_file_async(
+    path: impl AsRef<Path>,
+) -> Result<ShortcutFile, LinuxShortcutError> {
+    let read = tokio::fs::read_to_string(path.as_ref())
+        .await
+        .map_err(|err| LinuxShortcutError::from(err).at(path.as_ref()))?;
+    parse_desktop_entry(&read, ParseOptions::default())
+}
+/// Parses the content of a Desktop Entry.
+///
+/// The first group must be `[Desktop Entry]`.
+/// In lenient mode lines without a `=` are skipped and missing required keys are left empty.
+fn parse_desktop_entry(
+    read: &str,
+    options: ParseOptions,
+) -> Result<ShortcutFile, LinuxShortcutError> {
+    // Editors on Windows may start the file with a byte order mark.
+    let read = read.strip_prefix('\u{feff}').unwrap_or(read);
+    let first_line = read
+        .lines()
+        .map(str::trim)
+        .find(|v| !v.is_empty() && !v.starts_with('#'));
+    if first_line.and_then(group_name) != Some("Desktop Entry") {
+        return Err(LinuxShortcutError::NotADesktopEntry);
+    }
+    let mut entry_type = None;
     let mut name = None;
     let mut path = None;
+    let mut url = None;
     let mut icon = None;
     let mut description = None;
     let mut arguments = None;
     let mut working_directory = None;
     let mut show_terminal = false;
+    let mut no_display = false;
+    let mut hidden = false;
+    let mut only_show_in = None;
+    let mut not_show_in = None;
     let mut categories = None;
+    let mut env = vec![];
+    let mut hotkey = None;
+    let mut startup_wm_class = None;
+    let mut mime_types = None;
+    let mut keywords = None;
+    let mut implements = None;
+    let mut field_codes = vec![];
+    let mut generic_name = None;
+    let mut try_exec = None;
+    let mut exec = None;
+    let mut exec_prefix = None;
+    let mut localized_names = BTreeMap::new();
+    let mut localized_descriptions = BTreeMap::new();
+    let mut extra = BTreeMap::new();
+    let mut desktop_spec_version = None;
+    let mut startup_notify = None;
+    let mut dbus_activatable = None;
+    let mut single_main_window = None;
+    let mut prefers_non_default_gpu = None;
+    let mut action_ids = None;
+    let mut action_groups: BTreeMap<String, DesktopAction> = BTreeMap::new();
+    // Keys of unknown groups are skipped.
+    let mut in_desktop_entry = true;
+    let mut current_action = None;
+    let mut group = String::new();
+    let mut seen_keys = HashSet::new();
 
-    for line in read.lines() {
+    for (line_number, line) in read.lines().enumerate() {
         let line = line.trim();
-        if line.is_empty() {
+        if line.is_empty() || line.starts_with('#') {
             continue;
         }
-        if line.starts_with('#') {
+        if line.starts_with('[') {
+            let name = group_name(line).unwrap_or(line);
+            group = name.to_string();
+            in_desktop_entry = name == "Desktop Entry";
+            current_action = name
+                .strip_prefix("Desktop Action ")
+                .map(|v| v.trim().to_string());
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key, value),
+            // A key without a value is read as an empty value.
+            None if is_key(line) => (line, ""),
+            None if options.lenient => continue,
+            None => {
+                return Err(LinuxShortcutError::ParseError {
+                    line: line_number + 1,
+                    message: format!(
+                        "Expected a group header, comment or Key=Value. Found {:?}",
+                        line
+                    ),
+                })
+            }
+        };
+        if !seen_keys.insert((group.clone(), key.to_string())) {
+            match options.on_duplicate {
+                DuplicateKeys::LastWins => {}
+                DuplicateKeys::FirstWins => continue,
+                DuplicateKeys::Error => {
+                    return Err(LinuxShortcutError::DuplicateKey(key.to_string()))
+                }
+            }
+        }
+        if let Some(id) = &current_action {
+            let action = action_groups
+                .entry(id.clone())
+                .or_insert_with(|| DesktopAction::new(id, ""));
+            match key {
+                "Name" => action.name = unescape_string(value).into_owned(),
+                "Exec" => action.exec = split_exec(&unescape_string(value)),
+                "Icon" => action.icon = Some(PathBuf::from(value)),
+                _ => {}
+            }
+            continue;
+        }
+        if !in_desktop_entry {
+            continue;
+        }
+        if let Some((localized_key, locale)) = split_locale(key) {
+            match localized_key {
+                "Name" => {
+                    localized_names.insert(locale.to_string(), unescape_string(value).into_owned());
+                }
+                "Comment" => {
+                    localized_descriptions
+                        .insert(locale.to_string(), unescape_string(value).into_owned());
+                }
+                _ => {
+                    extra.insert(key.to_string(), unescape_string(value).into_owned());
+                }
+            }
             continue;
         }
-        let mut split = line.splitn(2, '=');
-        let key = split.next().unwrap();
-        let value = split.next().unwrap();
         match key {
-            "Name" => name = Some(value.to_string()),
+            "Type" => entry_type = Some(value.to_string()),
+            // The default version is read as `None` so it is written as the default again.
+            "Version" if value != DESKTOP_SPEC_VERSION => {
+                desktop_spec_version = Some(value.to_string());
+            }
+            "Version" => {}
+            "Name" => name = Some(unescape_string(value).into_owned()),
+            "GenericName" => generic_name = Some(unescape_string(value).into_owned()),
+            "TryExec" => try_exec = Some(PathBuf::from(value)),
+            "URL" => url = Some(decode_url(value).into_owned()),
             "Path" => {
                 working_directory = Some(PathBuf::from(value));
             }
@@ -122,58 +608,1316 @@ pub fn read_shortcut_file(path: impl AsRef<Path>) -> Result<ShortcutFile, LinuxS
                 icon = Some(PathBuf::from(value));
             }
             "Comment" => {
-                description = Some(value.to_string());
+                description = Some(unescape_string(value).into_owned());
             }
-            "Exec" => {
-                let mut split = value.split(" ");
-                let command = split.next().unwrap();
-                path = Some(PathBuf::from(command));
-                arguments = Some(split.map(|v| v.to_owned()).collect());
+            "Exec" if value.trim().is_empty() => {
+                if !options.lenient {
+                    return Err(LinuxShortcutError::MissingValue("Exec"));
+                }
             }
+            "Exec" => exec = Some(unescape_string(value).into_owned()),
+            EXEC_PREFIX_KEY => exec_prefix = Some(unescape_string(value).into_owned()),
             "Terminal" => {
                 show_terminal = value == "true";
             }
+            "NoDisplay" => {
+                no_display = value == "true";
+            }
+            "Hidden" => {
+                hidden = value == "true";
+            }
+            "OnlyShowIn" => {
+                only_show_in = Some(parse_list(value));
+            }
+            "NotShowIn" => {
+                not_show_in = Some(parse_list(value));
+            }
             "Categories" => {
-                categories = Some(value.split(';').map(|v| v.to_string()).collect());
+                categories = Some(parse_list(value));
+            }
+            "MimeType" => {
+                mime_types = Some(parse_list(value));
+            }
+            "Keywords" => {
+                keywords = Some(parse_list(value));
+            }
+            "Implements" => {
+                implements = Some(parse_list(value));
+            }
+            "StartupNotify" => {
+                startup_notify = Some(value == "true");
+            }
+            "DBusActivatable" => {
+                dbus_activatable = Some(value == "true");
+            }
+            "SingleMainWindow" => {
+                single_main_window = Some(value == "true");
+            }
+            "PrefersNonDefaultGPU" => {
+                prefers_non_default_gpu = Some(value == "true");
+            }
+            "StartupWMClass" => {
+                startup_wm_class = Some(value.to_string());
             }
-            _ => {}
+            "Actions" => {
+                action_ids = Some(parse_list(value));
+            }
+            // KDE allows keys without modifiers and lists of shortcuts which a Hotkey can not hold.
+            "X-KDE-Shortcuts" => match value.parse() {
+                Ok(value) => hotkey = Some(value),
+                Err(_) => {
+                    extra.insert(key.to_string(), unescape_string(value).into_owned());
+                }
+            },
+            _ => {
+                extra.insert(key.to_string(), unescape_string(value).into_owned());
+            }
+        }
+    }
+    if let Some(exec) = &exec {
+        let (mut split, codes) = split_exec_with_field_codes(exec);
+        let split_len = split.len();
+        // The prefix is only removed if every one of its arguments starts the command.
+        if let Some(prefix) = &exec_prefix {
+            let prefix = split_exec(prefix);
+            if split.starts_with(&prefix) {
+                split.drain(..prefix.len());
+            } else {
+                // Another program changed the Exec key.
+                exec_prefix = None;
+            }
+        }
+        let mut split = split.into_iter().peekable();
+        if split.next_if_eq("env").is_some() {
+            while let Some(variable) = split.next_if(|v| v.contains('=')) {
+                if let Some((key, value)) = variable.split_once('=') {
+                    env.push((key.to_owned(), value.to_owned()));
+                }
+            }
+        }
+        let command = split.next().unwrap_or_default();
+        path = Some(PathBuf::from(command));
+        let rest: Vec<String> = split.collect();
+        // Positions are counted from the first argument after the command.
+        let consumed = split_len - rest.len();
+        field_codes = codes
+            .into_iter()
+            .map(|(position, code)| (position.saturating_sub(consumed), code))
+            .collect();
+        arguments = Some(rest);
+    }
+    // The translation for the locale replaces the untranslated value.
+    if let Some(locale) = &options.locale {
+        if let Some(localized) = find_localized(&localized_names, locale) {
+            name = Some(localized.clone());
+        }
+        if let Some(localized) = find_localized(&localized_descriptions, locale) {
+            description = Some(localized.clone());
         }
     }
+    let target = match entry_type.as_deref() {
+        Some("Directory") => ShortcutTarget::default(),
+        Some("Link") => ShortcutTarget::Url(required(url, "URL", &options)?),
+        _ => ShortcutTarget::Path(required(path, "Path", &options)?),
+    };
+    // Only the actions listed in the `Actions` key are used.
+    let actions = action_ids
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|id| action_groups.remove(id))
+        .collect();
     let shortcut = ShortcutFile {
-        name: name.ok_or(LinuxShortcutError::MissingValue("Name"))?,
-        path: path.ok_or(LinuxShortcutError::MissingValue("Path"))?,
+        name: required(name, "Name", &options)?,
+        target,
         icon,
         description,
         arguments: arguments.unwrap_or_default(),
         working_directory,
         show_terminal,
         categories: categories.unwrap_or_default(),
+        hotkey,
+        startup_wm_class,
+        mime_types: mime_types.unwrap_or_default(),
+        keywords: keywords.unwrap_or_default(),
+        localized_names,
+        localized_descriptions,
+        generic_name,
+        no_display,
+        hidden,
+        only_show_in: only_show_in.unwrap_or_default(),
+        not_show_in: not_show_in.unwrap_or_default(),
+        env,
+        extra,
+        actions,
+        desktop_spec_version,
+        startup_notify,
+        dbus_activatable,
+        single_main_window,
+        prefers_non_default_gpu,
+        try_exec,
+        exec_prefix,
+        implements: implements.unwrap_or_default(),
+        field_codes,
+        // Applications and links are inferred from the target.
+        entry_type: (entry_type.as_deref() == Some("Directory")).then_some(EntryType::Directory),
+        ..Default::default()
     };
     Ok(shortcut)
 }
+/// Returns the name of a group header without the brackets. `[ Desktop Entry ]` is `Desktop Entry`
+fn group_name(line: &str) -> Option<&str> {
+    line.strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+/// Returns the value of a required key.
+///
+/// In lenient mode a missing key is replaced with the default value.
+fn required<T: Default>(
+    value: Option<T>,
+    key: &'static str,
+    options: &ParseOptions,
+) -> Result<T, LinuxShortcutError> {
+    match value {
+        Some(value) => Ok(value),
+        None if options.lenient => Ok(T::default()),
+        None => Err(LinuxShortcutError::MissingValue(key)),
+    }
+}
+/// Checks if a line without a `=` is a key. Such as `Terminal` or `Name[de]`
+fn is_key(line: &str) -> bool {
+    let key = split_locale(line).map_or(line, |(key, _)| key);
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+/// Finds the best translation for a locale such as `de_DE.UTF-8`
+///
+/// Tries `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER` and then `lang` as in the Desktop Entry spec.
+fn find_localized<'a>(values: &'a BTreeMap<String, String>, locale: &str) -> Option<&'a String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    let locale = locale.split_once('.').map_or(locale, |(locale, _)| locale);
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+    let mut candidates = vec![];
+    if let Some(country) = country {
+        if let Some(modifier) = modifier {
+            candidates.push(format!("{}_{}@{}", lang, country, modifier));
+        }
+        candidates.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        candidates.push(format!("{}@{}", lang, modifier));
+    }
+    candidates.push(lang.to_string());
+    candidates.iter().find_map(|v| values.get(v))
+}
+/// Splits a localized key such as `Name[de]` into the key and the locale.
+///
+/// Returns `None` if the key is not localized.
+fn split_locale(key: &str) -> Option<(&str, &str)> {
+    let (key, locale) = key.strip_suffix(']')?.split_once('[')?;
+    Some((key, locale))
+}
+/// Keys written from the fields of a [ShortcutFile]. They can not be set with [ShortcutFile::extra].
+const RESERVED_KEYS: &[&str] = &[
+    "Type",
+    "Version",
+    "Name",
+    "GenericName",
+    "Exec",
+    EXEC_PREFIX_KEY,
+    "TryExec",
+    "URL",
+    "Path",
+    "Icon",
+    "Comment",
+    "Terminal",
+    "NoDisplay",
+    "Hidden",
+    "OnlyShowIn",
+    "NotShowIn",
+    "Categories",
+    "MimeType",
+    "Keywords",
+    "Implements",
+    "Actions",
+    "StartupNotify",
+    "DBusActivatable",
+    "SingleMainWindow",
+    "PrefersNonDefaultGPU",
+    "StartupWMClass",
+];
+/// Checks that a key of [ShortcutFile::extra] is made of `A-Z`, `a-z`, `0-9` and `-` with an optional locale.
+///
+/// Keys in [RESERVED_KEYS], translations of `Name` and `Comment` and `X-KDE-Shortcuts` if a hotkey is set are rejected.
+fn is_valid_extra_key(key: &str, has_hotkey: bool) -> bool {
+    let (base, locale) = match split_locale(key) {
+        Some((base, locale)) => (base, Some(locale)),
+        None => (key, None),
+    };
+    let valid_base =
+        !base.is_empty() && base.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    let valid_locale = locale.is_none_or(|v| {
+        !v.is_empty()
+            && v.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '@' | '.' | '-'))
+    });
+    let reserved = match locale {
+        Some(_) => matches!(base, "Name" | "Comment"),
+        None => RESERVED_KEYS.contains(&base) || (has_hotkey && base == "X-KDE-Shortcuts"),
+    };
+    valid_base && valid_locale && !reserved
+}
+/// Checks that an action id only contains `A-Z`, `a-z`, `0-9` and `-` as required for group names.
+fn is_valid_action_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+/// Escapes a string value following the Desktop Entry spec.
+///
+/// `\`, newlines, tabs, carriage returns and leading spaces are escaped with a backslash.
+fn escape_string(value: &str) -> Cow<'_, str> {
+    let needs_escape = value.starts_with(' ') || value.contains(['\\', '\n', '\t', '\r']);
+    if !needs_escape {
+        return Cow::Borrowed(value);
+    }
+    let mut escaped = String::with_capacity(value.len());
+    let mut leading = true;
+    for c in value.chars() {
+        leading &= c == ' ';
+        match c {
+            ' ' if leading => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+/// Reverses [escape_string]
+///
+/// Unknown escape sequences are kept as is.
+fn unescape_string(value: &str) -> Cow<'_, str> {
+    if !value.contains('\\') {
+        return Cow::Borrowed(value);
+    }
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    Cow::Owned(unescaped)
+}
+/// Formats a list value. Values are separated by `;`
+///
+/// The last value is followed by a `;` if [WriteOptions::trailing_semicolons] is set.
+fn format_list(values: &[String], options: &WriteOptions) -> String {
+    let mut list = values.join(";");
+    if options.trailing_semicolons && !list.is_empty() {
+        list.push(';');
+    }
+    list
+}
+/// Parses a list value separated by `;`. The trailing `;` is optional.
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+/// Key storing [ShortcutFile::exec_prefix] so it can be removed from `Exec` when reading.
+const EXEC_PREFIX_KEY: &str = "X-Exec-Prefix";
+/// Characters that require an argument of the `Exec` key to be quoted.
+const EXEC_RESERVED_CHARACTERS: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')',
+    '`',
+];
+/// Quotes an argument of the `Exec` key following the Desktop Entry spec.
+///
+/// Arguments containing reserved characters are wrapped in double quotes and
+/// `"`, `` ` ``, `$` and `\` are escaped with a backslash.
+fn quote_exec_argument(argument: &str) -> Cow<'_, str> {
+    if !argument.is_empty() && !argument.contains(EXEC_RESERVED_CHARACTERS) {
+        return Cow::Borrowed(argument);
+    }
+    let mut quoted = String::with_capacity(argument.len() + 2);
+    quoted.push('"');
+    for c in argument.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+/// Field codes that can be used in the `Exec` key. Including the deprecated ones.
+const EXEC_FIELD_CODES: &[&str] = &[
+    "%f", "%F", "%u", "%U", "%d", "%D", "%n", "%N", "%i", "%c", "%k", "%v", "%m",
+];
+/// Letters of the field codes that are still part of the Desktop Entry spec.
+const VALID_FIELD_CODES: &[char] = &['f', 'F', 'u', 'U', 'i', 'c', 'k'];
+/// Letters of the field codes that were removed from the Desktop Entry spec.
+const DEPRECATED_FIELD_CODES: &[char] = &['d', 'D', 'n', 'N', 'v', 'm'];
+/// Escapes and quotes an argument of the `Exec` key.
+///
+/// A `%` that does not start a valid field code is escaped as `%%`.
+/// Then the argument is quoted with [quote_exec_argument].
+fn escape_exec_argument(argument: &str) -> Cow<'_, str> {
+    if !argument.contains('%') {
+        return quote_exec_argument(argument);
+    }
+    let mut escaped = String::with_capacity(argument.len() + 1);
+    let mut chars = argument.chars().peekable();
+    while let Some(c) = chars.next() {
+        escaped.push(c);
+        if c == '%' {
+            match chars.next_if(|v| VALID_FIELD_CODES.contains(v)) {
+                Some(code) => escaped.push(code),
+                None => escaped.push('%'),
+            }
+        }
+    }
+    Cow::Owned(quote_exec_argument(&escaped).into_owned())
+}
+/// Composes the value of the `Exec` key.
+///
+/// The command and arguments are escaped with [escape_exec_argument] and joined by a single space.
+/// Field codes such as `%U` are placed before the argument at their position without quoting.
+/// Field codes with a position past the last argument are placed at the end.
+fn compose_exec(command: &str, arguments: &[String], field_codes: &[(usize, &str)]) -> String {
+    let mut exec = escape_exec_argument(command).into_owned();
+    let mut push = |value: &str| {
+        exec.push(' ');
+        exec.push_str(value);
+    };
+    for (index, argument) in arguments.iter().enumerate() {
+        for (_, field_code) in field_codes.iter().filter(|(v, _)| *v == index) {
+            push(field_code);
+        }
+        push(&escape_exec_argument(argument));
+    }
+    for (_, field_code) in field_codes.iter().filter(|(v, _)| *v >= arguments.len()) {
+        push(field_code);
+    }
+    exec
+}
+/// Returns the first deprecated field code such as `%d` in the argument.
+pub(super) fn find_deprecated_field_code(argument: &str) -> Option<String> {
+    let mut chars = argument.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.next() {
+                Some(code) if DEPRECATED_FIELD_CODES.contains(&code) => {
+                    return Some(format!("%{}", code));
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+/// Splits the value of the `Exec` key into its arguments.
+///
+/// Reverses [escape_exec_argument] on a value already passed through [unescape_string].
+/// Unquoted field codes such as `%f` or `%U` are removed.
+fn split_exec(value: &str) -> Vec<String> {
+    split_exec_with_field_codes(value).0
+}
+/// Same as [split_exec] but also returns the field codes that were removed.
+///
+/// Deprecated field codes such as `%d` are dropped as the spec requires.
+fn split_exec_with_field_codes(value: &str) -> (Vec<String>, Vec<(usize, String)>) {
+    let mut arguments = vec![];
+    let mut field_codes = vec![];
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut was_quoted = false;
+    let mut quoted = false;
+    let mut chars = value.chars();
+    let mut push_argument = |argument: String, was_quoted: bool| {
+        if was_quoted || !EXEC_FIELD_CODES.contains(&argument.as_str()) {
+            arguments.push(argument.replace("%%", "%"));
+        } else if find_deprecated_field_code(&argument).is_none() {
+            field_codes.push((arguments.len(), argument));
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+                in_argument = true;
+            }
+            '\\' if quoted => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_argument {
+                    push_argument(std::mem::take(&mut current), was_quoted);
+                    in_argument = false;
+                    was_quoted = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        push_argument(current, was_quoted);
+    }
+    (arguments, field_codes)
+}
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use crate::shortcut_files::{linux::save_shortcut_file, ShortcutFile};
+    use crate::shortcut_files::{
+        linux::{save_shortcut_file, save_shortcut_file_with_options},
+        DesktopAction, EntryType, Hotkey, HotkeyKey, ParseOptions, ShortcutFile, ShortcutTarget,
+    };
 
-    use super::read_shortcut_file;
+    use super::{
+        compose_exec, escape_exec_argument, escape_string, find_deprecated_field_code,
+        parse_desktop_entry, quote_exec_argument, read_shortcut_file, render_desktop_entry,
+        split_exec, unescape_string, LinuxShortcutError,
+    };
 
     #[test]
     fn test_save_shortcut_file() {
         let shortcut = ShortcutFile {
             name: "Test".to_string(),
-            path: PathBuf::from("/usr/bin/ls"),
+            target: ShortcutTarget::Path(PathBuf::from("/usr/bin/ls")),
             icon: Some(PathBuf::from("/usr/share/icons/ls.png")),
             description: Some("This is a test shortcut".to_string()),
             arguments: vec!["-l".to_string()],
             working_directory: None,
             show_terminal: false,
             categories: vec!["Utility".to_string(), "System".to_string()],
+            ..Default::default()
         };
-        let path = PathBuf::from("test.desktop");
+        let path = std::env::temp_dir().join("test.desktop");
         save_shortcut_file(shortcut.clone(), &path).unwrap();
         let content = read_shortcut_file(path).unwrap();
         assert_eq!(shortcut, content);
     }
+    #[test]
+    fn test_hotkey() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .hotkey(Hotkey::new(HotkeyKey::Char('P')).ctrl().alt());
+        let path = std::env::temp_dir().join("test_hotkey.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("X-KDE-Shortcuts=Ctrl+Alt+P"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_quote_exec_argument() {
+        assert_eq!(quote_exec_argument("-l"), "-l");
+        assert_eq!(
+            quote_exec_argument("/home/My Files/x.txt"),
+            "\"/home/My Files/x.txt\""
+        );
+        assert_eq!(quote_exec_argument("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_exec_argument("$HOME"), "\"\\$HOME\"");
+        assert_eq!(quote_exec_argument(""), "\"\"");
+    }
+    #[test]
+    fn test_split_exec() {
+        assert_eq!(
+            split_exec("/usr/bin/app \"/home/My Files/x.txt\" \"\\$HOME\" -l"),
+            vec!["/usr/bin/app", "/home/My Files/x.txt", "$HOME", "-l"]
+        );
+        assert_eq!(
+            split_exec("\"/opt/My App/bin/run\" --open %U \"%f\""),
+            vec!["/opt/My App/bin/run", "--open", "%f"]
+        );
+    }
+    #[test]
+    fn test_quoted_path() {
+        let shortcut = ShortcutFile::new("Test", "/opt/My App/bin/run").arg("--flag");
+        let path = std::env::temp_dir().join("test_quoted_path.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Exec=\"/opt/My App/bin/run\" --flag"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_quoted_arguments() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("/home/My Files/x.txt")
+            .arg("say \"hi\"")
+            .arg("$HOME")
+            .arg("back\\slash");
+        let path = std::env::temp_dir().join("test_quoted_arguments.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_url() {
+        let shortcut = ShortcutFile::new_url("Test", "https://example.com")
+            .icon("/usr/share/icons/example.png");
+        let path = std::env::temp_dir().join("test_url.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Type=Link"));
+        assert!(content.contains("URL=https://example.com"));
+        assert!(!content.contains("Exec="));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_startup_wm_class() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").startup_wm_class("test-app");
+        let path = std::env::temp_dir().join("test_startup_wm_class.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("StartupWMClass=test-app"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_mime_types() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .mime_type("text/plain")
+            .mime_type("image/png");
+        let path = std::env::temp_dir().join("test_mime_types.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("MimeType=text/plain;image/png;"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_keywords() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .keyword("list")
+            .keyword("files");
+        let path = std::env::temp_dir().join("test_keywords.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Keywords=list;files;"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_localized() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .description("List files")
+            .localized_name("de", "Prüfung")
+            .localized_name("fr_CA", "Essai")
+            .localized_description("de", "Dateien auflisten")
+            .localized_description("fr_CA", "Lister les fichiers");
+        let path = std::env::temp_dir().join("test_localized.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Name[de]=Prüfung"));
+        assert!(content.contains("Comment[fr_CA]=Lister les fichiers"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_executable() {
+        use std::os::unix::fs::PermissionsExt;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls");
+        let path = std::env::temp_dir().join("test_executable.desktop");
+        save_shortcut_file(shortcut, &path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_ne!(mode & 0o111, 0);
+    }
+    #[test]
+    fn test_generic_name() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").generic_name("File Lister");
+        let path = std::env::temp_dir().join("test_generic_name.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("GenericName=File Lister"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_no_display() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").no_display();
+        let path = std::env::temp_dir().join("test_no_display.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("NoDisplay=true"));
+        assert!(!content.contains("Hidden="));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_hidden() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").hidden();
+        let path = std::env::temp_dir().join("test_hidden.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Hidden=true"));
+        assert!(!content.contains("NoDisplay="));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_show_in() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .only_show_in("GNOME")
+            .not_show_in("KDE");
+        let path = std::env::temp_dir().join("test_show_in.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("OnlyShowIn=GNOME;"));
+        assert!(content.contains("NotShowIn=KDE;"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_env() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/wine")
+            .env("WINEPREFIX", "/home/me/My Wine")
+            .env("WINEDEBUG", "-all")
+            .arg("app.exe");
+        let path = std::env::temp_dir().join("test_env.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(
+            "Exec=env \"WINEPREFIX=/home/me/My Wine\" WINEDEBUG=-all /usr/bin/wine app.exe"
+        ));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_escape_exec_argument() {
+        assert_eq!(escape_exec_argument("100%"), "100%%");
+        assert_eq!(escape_exec_argument("%U"), "%U");
+        assert_eq!(escape_exec_argument("--file=%f"), "--file=%f");
+        assert_eq!(escape_exec_argument("50% off"), "\"50%% off\"");
+        assert_eq!(
+            find_deprecated_field_code("--dir=%d"),
+            Some("%d".to_string())
+        );
+        assert_eq!(find_deprecated_field_code("%U"), None);
+    }
+    #[test]
+    fn test_field_codes() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("100%")
+            .arg("%U");
+        let path = std::env::temp_dir().join("test_field_codes.desktop");
+        save_shortcut_file(shortcut, &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Exec=/usr/bin/ls 100%% %U"));
+        assert_eq!(read_shortcut_file(path).unwrap().arguments, vec!["100%"]);
+    }
+    #[test]
+    fn test_extra() {
+        let path = std::env::temp_dir().join("test_extra.desktop");
+        std::fs::write(
+            &path,
+            "[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/ls\nX-GNOME-Autostart-enabled=true\nGenericName[de]=Test\n\n[Desktop Action new]\nName=New\n",
+        )
+        .unwrap();
+        let shortcut = read_shortcut_file(&path).unwrap();
+        assert_eq!(
+            shortcut
+                .extra
+                .get("X-GNOME-Autostart-enabled")
+                .map(String::as_str),
+            Some("true")
+        );
+        assert_eq!(shortcut.extra.len(), 2);
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("X-GNOME-Autostart-enabled=true"));
+        assert!(content.contains("GenericName[de]=Test"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_not_a_desktop_entry() {
+        let path = std::env::temp_dir().join("test_not_a_desktop_entry.txt");
+        std::fs::write(&path, "Hello World\nName=Test\n").unwrap();
+        assert!(matches!(
+            read_shortcut_file(path),
+            Err(LinuxShortcutError::NotADesktopEntry)
+        ));
+    }
+    #[test]
+    fn test_actions() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/firefox")
+            .action(
+                DesktopAction::new("new-window", "New Window")
+                    .arg("/usr/bin/firefox")
+                    .arg("--new-window"),
+            )
+            .action(
+                DesktopAction::new("private", "New Private Window")
+                    .arg("/usr/bin/firefox")
+                    .arg("--private-window")
+                    .icon("/usr/share/icons/private.png"),
+            );
+        let path = std::env::temp_dir().join("test_actions.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Actions=new-window;private;"));
+        assert!(content.contains(
+            "[Desktop Action new-window]\nName=New Window\nExec=/usr/bin/firefox --new-window\n"
+        ));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_desktop_spec_version() {
+        let path = std::env::temp_dir().join("test_desktop_spec_version.desktop");
+        save_shortcut_file(ShortcutFile::new("Test", "/usr/bin/ls"), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("[Desktop Entry]\nVersion=1.5\n"));
+
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").desktop_spec_version("1.0");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("[Desktop Entry]\nVersion=1.0\n"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_startup_notify() {
+        let path = std::env::temp_dir().join("test_startup_notify.desktop");
+        for startup_notify in [true, false] {
+            let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").startup_notify(startup_notify);
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.contains(&format!("StartupNotify={}", startup_notify)));
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+    #[test]
+    fn test_directory() {
+        let mut shortcut = ShortcutFile::default().entry_type(EntryType::Directory);
+        shortcut.name = "Games".to_string();
+        let path = std::env::temp_dir().join("test_directory.directory");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Type=Directory\n"));
+        assert!(!content.contains("Exec="));
+        assert!(!content.contains("Terminal="));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_overwrite_longer_file() {
+        let path = std::env::temp_dir().join("test_overwrite_longer_file.desktop");
+        let long = ShortcutFile::new("A Long Shortcut Name", "/usr/bin/ls")
+            .description("This description makes the file longer")
+            .category("Utility");
+        save_shortcut_file(long, &path).unwrap();
+        let short = ShortcutFile::new("Short", "/usr/bin/ls");
+        save_shortcut_file(short.clone(), &path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            render_desktop_entry(short).unwrap()
+        );
+    }
+    #[test]
+    fn test_escape_string() {
+        assert_eq!(escape_string("Test"), "Test");
+        assert_eq!(escape_string("  Two\nLines"), "\\s\\sTwo\\nLines");
+        assert_eq!(escape_string("C:\\Path\tTab"), "C:\\\\Path\\tTab");
+        for value in ["  Two\nLines", "C:\\Path\tTab", "Unknown \\q"] {
+            assert_eq!(unescape_string(&escape_string(value)), value);
+        }
+        assert_eq!(unescape_string("Unknown \\q"), "Unknown \\q");
+
+        let shortcut =
+            ShortcutFile::new("First\nSecond", "/usr/bin/ls").description("Back\\slash and\ttab");
+        let path = std::env::temp_dir().join("test_escape_string.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Name=First\\nSecond\n"));
+        assert!(content.contains("Comment=Back\\\\slash and\\ttab\n"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_dbus_activatable() {
+        let path = std::env::temp_dir().join("test_dbus_activatable.desktop");
+        for dbus_activatable in [true, false] {
+            let shortcut =
+                ShortcutFile::new("Test", "/usr/bin/ls").dbus_activatable(dbus_activatable);
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.contains(&format!("DBusActivatable={}", dbus_activatable)));
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+    #[test]
+    fn test_single_main_window() {
+        let path = std::env::temp_dir().join("test_single_main_window.desktop");
+        for single_main_window in [true, false] {
+            let shortcut =
+                ShortcutFile::new("Test", "/usr/bin/ls").single_main_window(single_main_window);
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.contains(&format!("SingleMainWindow={}", single_main_window)));
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+    #[test]
+    fn test_prefers_non_default_gpu() {
+        let path = std::env::temp_dir().join("test_prefers_non_default_gpu.desktop");
+        for prefers in [true, false] {
+            let shortcut =
+                ShortcutFile::new("Test", "/usr/bin/ls").prefers_non_default_gpu(prefers);
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.contains(&format!("PrefersNonDefaultGPU={}", prefers)));
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+    #[test]
+    fn test_trailing_semicolons() {
+        use crate::shortcut_files::WriteOptions;
+        let path = std::env::temp_dir().join("test_trailing_semicolons.desktop");
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .category("Utility")
+            .category("System");
+        for (trailing_semicolons, categories) in [
+            (true, "Categories=Utility;System;\n"),
+            (false, "Categories=Utility;System\n"),
+        ] {
+            let options = WriteOptions::default().trailing_semicolons(trailing_semicolons);
+            save_shortcut_file_with_options(shortcut.clone(), &path, options).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(content.contains(categories));
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+    #[test]
+    fn test_shell_location() {
+        let shortcut = ShortcutFile::new_shell("Test", "shell:RecycleBinFolder");
+        assert!(matches!(
+            render_desktop_entry(shortcut),
+            Err(LinuxShortcutError::UnsupportedTarget(
+                ShortcutTarget::Shell(_)
+            ))
+        ));
+    }
+    #[test]
+    fn test_try_exec() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").try_exec("/opt/does/not/exist");
+        let path = std::env::temp_dir().join("test_try_exec.desktop");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("TryExec=/opt/does/not/exist\n"));
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_empty_exec() {
+        let content = "[Desktop Entry]\nType=Application\nName=Test\nExec=\n";
+        assert!(matches!(
+            parse_desktop_entry(content, ParseOptions::default()),
+            Err(LinuxShortcutError::MissingValue("Exec"))
+        ));
+        let shortcut = parse_desktop_entry(content, ParseOptions::default().lenient()).unwrap();
+        assert_eq!(shortcut.target, ShortcutTarget::Path(PathBuf::new()));
+    }
+    #[test]
+    fn test_key_without_value() {
+        let content = "[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/ls\nTerminal\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert!(!shortcut.show_terminal);
+        assert_eq!(shortcut, ShortcutFile::new("Test", "/usr/bin/ls"));
+    }
+    #[test]
+    fn test_exec_prefix() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/app")
+            .exec_prefix("env FOO=bar")
+            .arg("--flag");
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains("Exec=env FOO=bar /usr/bin/app --flag\n"));
+        let read = parse_desktop_entry(&content, ParseOptions::default()).unwrap();
+        assert!(read.env.is_empty());
+        assert_eq!(shortcut, read);
+
+        let changed = content.replace("FOO=bar ", "FOO=barbaz ");
+        let read = parse_desktop_entry(&changed, ParseOptions::default()).unwrap();
+        assert_eq!(read.exec_prefix, None);
+        assert_eq!(read.env, vec![("FOO".to_owned(), "barbaz".to_owned())]);
+        assert_eq!(read.target, ShortcutTarget::Path("/usr/bin/app".into()));
+    }
+    #[test]
+    fn test_duplicate_keys() {
+        use crate::shortcut_files::DuplicateKeys;
+        let content =
+            "[Desktop Entry]\nType=Application\nName=First\nExec=/usr/bin/ls\nName=Second\n\n[Desktop Action new]\nName=Other\n";
+        let parse = |on_duplicate| {
+            parse_desktop_entry(content, ParseOptions::default().on_duplicate(on_duplicate))
+        };
+        assert_eq!(parse(DuplicateKeys::LastWins).unwrap().name, "Second");
+        assert_eq!(parse(DuplicateKeys::FirstWins).unwrap().name, "First");
+        assert!(matches!(
+            parse(DuplicateKeys::Error),
+            Err(LinuxShortcutError::DuplicateKey(key)) if key == "Name"
+        ));
+        assert_eq!(
+            parse_desktop_entry(content, ParseOptions::default())
+                .unwrap()
+                .name,
+            "Second"
+        );
+    }
+    #[test]
+    fn test_locale() {
+        let content = "[Desktop Entry]\nType=Application\nName=Test\nName[de]=Prüfung\nComment=A test\nExec=/usr/bin/ls\n";
+        let shortcut =
+            parse_desktop_entry(content, ParseOptions::default().locale("de_DE.UTF-8")).unwrap();
+        assert_eq!(shortcut.name, "Prüfung");
+        assert_eq!(shortcut.description.as_deref(), Some("A test"));
+        assert_eq!(
+            shortcut.localized_names.get("de").map(String::as_str),
+            Some("Prüfung")
+        );
+        let shortcut = parse_desktop_entry(content, ParseOptions::default().locale("fr")).unwrap();
+        assert_eq!(shortcut.name, "Test");
+    }
+    #[test]
+    fn test_compose_exec() {
+        assert_eq!(
+            compose_exec("/opt/My App/run", &["--flag".into(), "a b".into()], &[]),
+            "\"/opt/My App/run\" --flag \"a b\""
+        );
+        assert_eq!(compose_exec("/usr/bin/ls", &[], &[]), "/usr/bin/ls");
+        assert_eq!(
+            compose_exec("/opt/My App/run", &["--open".into()], &[(1, "%U")]),
+            "\"/opt/My App/run\" --open %U"
+        );
+        assert_eq!(
+            compose_exec("/usr/bin/ls", &[], &[(0, "%U")]),
+            "/usr/bin/ls %U"
+        );
+    }
+    #[test]
+    fn test_crlf() {
+        use super::{read_desktop_entry, write_desktop_entry_with_options};
+        use crate::shortcut_files::{LineEnding, WriteOptions};
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("-l")
+            .category("Utility");
+        let mut buffer = Vec::new();
+        write_desktop_entry_with_options(
+            shortcut.clone(),
+            &mut buffer,
+            WriteOptions::default().line_ending(LineEnding::CrLf),
+        )
+        .unwrap();
+        let content = String::from_utf8(buffer).unwrap();
+        assert!(content.starts_with("[Desktop Entry]\r\n"));
+        assert!(content.contains("Exec=/usr/bin/ls -l\r\n"));
+        assert!(!content.replace("\r\n", "").contains('\n'));
+
+        let read = read_desktop_entry(content.as_bytes(), ParseOptions::default()).unwrap();
+        assert_eq!(read.name, "Test");
+        assert_eq!(read.arguments, vec!["-l"]);
+        assert_eq!(read.categories, vec!["Utility"]);
+    }
+    #[test]
+    fn test_flatpak() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/flatpak")
+            .arg("run")
+            .arg("org.example.App")
+            .implements("org.freedesktop.FileManager1")
+            .extra("X-Flatpak", "org.example.App");
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains("Implements=org.freedesktop.FileManager1;\n"));
+        assert!(content.contains("X-Flatpak=org.example.App\n"));
+        let read = parse_desktop_entry(&content, ParseOptions::default()).unwrap();
+        assert_eq!(read.extra.get("X-Flatpak").unwrap(), "org.example.App");
+        assert_eq!(shortcut, read);
+    }
+    #[test]
+    fn test_encoded_url() {
+        for url in ["https://x.test/a b", "https://x.test/?q=café"] {
+            let shortcut = ShortcutFile::new_url("Test", url);
+            let content = render_desktop_entry(shortcut.clone()).unwrap();
+            assert!(!content.contains("URL=https://x.test/a b"));
+            assert!(content.is_ascii());
+            assert_eq!(
+                shortcut,
+                parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+            );
+        }
+    }
+    #[test]
+    fn test_byte_order_mark() {
+        let content = "\u{feff}[ Desktop Entry ]\nType=Application\nName=Test\nExec=/usr/bin/ls\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert_eq!(shortcut.name, "Test");
+        assert_eq!(shortcut.target, ShortcutTarget::Path("/usr/bin/ls".into()));
+
+        let path = std::env::temp_dir().join("test_byte_order_mark.desktop");
+        std::fs::write(&path, content).unwrap();
+        assert_eq!(read_shortcut_file(&path).unwrap(), shortcut);
+    }
+    #[test]
+    fn test_group_name() {
+        use crate::shortcut_files::WriteOptions;
+        let path = std::env::temp_dir().join("test_group_name.desktop");
+        save_shortcut_file_with_options(
+            ShortcutFile::new("Test", "/usr/bin/ls"),
+            &path,
+            WriteOptions::default().group_name("KDE Desktop Entry"),
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("[KDE Desktop Entry]\n"));
+    }
+    #[test]
+    fn test_parse_error_line() {
+        let content = "# Comment\n[Desktop Entry]\nType=Application\n\nName=Test\nNot a key\nExec=/usr/bin/ls\n";
+        assert!(matches!(
+            parse_desktop_entry(content, ParseOptions::default()),
+            Err(LinuxShortcutError::ParseError { line: 6, .. })
+        ));
+    }
+    #[test]
+    fn test_omit_defaults() {
+        use super::write_desktop_entry_with_options;
+        use crate::shortcut_files::WriteOptions;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls");
+        let mut buffer = Vec::new();
+        write_desktop_entry_with_options(
+            shortcut.clone(),
+            &mut buffer,
+            WriteOptions::default().omit_defaults(true),
+        )
+        .unwrap();
+        let content = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            content,
+            "[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/ls\n"
+        );
+        assert!(!content.contains("Terminal="));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+    }
+    #[test]
+    fn test_interleaved_field_codes() {
+        let content = "[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/app %U --foo\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert_eq!(shortcut.arguments, vec!["--foo"]);
+        assert_eq!(shortcut.field_codes, vec![(0, "%U".to_owned())]);
+        let rendered = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(rendered.contains("Exec=/usr/bin/app %U --foo\n"));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&rendered, ParseOptions::default()).unwrap()
+        );
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_save_keeps_file() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let dir = std::env::temp_dir().join("test_failed_save_keeps_file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Test.desktop");
+        save_shortcut_file(ShortcutFile::new("Test", "/usr/bin/ls"), &path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        // Fails while writing as the target is not valid UTF-8.
+        let invalid = ShortcutFile::new("Test", OsStr::from_bytes(b"/usr/bin/\xFF"));
+        assert!(matches!(
+            save_shortcut_file(invalid, &path),
+            Err(LinuxShortcutError::PathNotValidUTF8)
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+    #[test]
+    fn test_read_entry_types() {
+        let content = "[Desktop Entry]\nName=Example\nURL=https://example.com\nType=Link\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert_eq!(
+            shortcut.target,
+            ShortcutTarget::Url("https://example.com".into())
+        );
+        assert!(matches!(
+            parse_desktop_entry(
+                "[Desktop Entry]\nType=Link\nName=Example\n",
+                ParseOptions::default()
+            ),
+            Err(LinuxShortcutError::MissingValue("URL"))
+        ));
+        let content = "[Desktop Entry]\nType=Directory\nName=Games\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert_eq!(shortcut.entry_type, Some(EntryType::Directory));
+    }
+    #[test]
+    fn test_io_error_path() {
+        let path = std::env::temp_dir()
+            .join("test_io_error_path")
+            .join("does_not_exist")
+            .join("Test.desktop");
+        let err = save_shortcut_file(ShortcutFile::new("Test", "/usr/bin/ls"), &path).unwrap_err();
+        assert!(matches!(&err, LinuxShortcutError::IoAt { path: at, .. } if at == &path));
+        assert!(err.to_string().contains("does_not_exist"));
+
+        let err = read_shortcut_file(&path).unwrap_err();
+        assert!(matches!(&err, LinuxShortcutError::IoAt { path: at, .. } if at == &path));
+    }
+    #[test]
+    fn test_appimage_keys() {
+        let shortcut = ShortcutFile::new("Test", "/opt/Test.AppImage")
+            .try_exec("/opt/Test.AppImage")
+            .extra("X-AppImage-Version", "1.2.3")
+            .action(DesktopAction::new("new", "New").arg("/opt/Test.AppImage"));
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        // Extra keys are the last keys of the [Desktop Entry] group.
+        let (entry, _) = content.split_once("\n\n[Desktop Action new]").unwrap();
+        assert!(entry.ends_with("X-AppImage-Version=1.2.3"));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+    }
+    #[test]
+    fn test_exec_string_escaping() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/echo")
+            .arg("$HOME")
+            .arg("a\"b")
+            .arg("a\\b")
+            .arg("two\nlines")
+            .action(
+                DesktopAction::new("back", "Back")
+                    .arg("/usr/bin/echo")
+                    .arg("a\\b"),
+            );
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains(
+            "Exec=/usr/bin/echo \"\\\\$HOME\" \"a\\\\\"b\" \"a\\\\\\\\b\" \"two\\nlines\"\n"
+        ));
+        assert!(content.contains("Exec=/usr/bin/echo \"a\\\\\\\\b\"\n"));
+        assert!(!content.lines().any(|line| line == "lines\""));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+    }
+    #[test]
+    fn test_unsupported_kde_shortcut() {
+        for value in ["Print", "Meta+Space", "Ctrl+Alt+T,Meta+T"] {
+            let content = format!(
+                "[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/ls\nX-KDE-Shortcuts={}\n",
+                value
+            );
+            let shortcut = parse_desktop_entry(&content, ParseOptions::default()).unwrap();
+            assert_eq!(shortcut.hotkey, None);
+            assert_eq!(shortcut.extra.get("X-KDE-Shortcuts").unwrap(), value);
+            assert!(render_desktop_entry(shortcut)
+                .unwrap()
+                .contains(&format!("X-KDE-Shortcuts={}\n", value)));
+        }
+    }
+    #[test]
+    fn test_action_escaping() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .action(DesktopAction::new("two-lines", "Two\nLines\\").arg("/usr/bin/ls"));
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains("Name=Two\\nLines\\\\\n"));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+        for id in ["new window", "new]", "", "new;"] {
+            let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+                .action(DesktopAction::new(id, "New").arg("/usr/bin/ls"));
+            assert!(matches!(
+                render_desktop_entry(shortcut),
+                Err(LinuxShortcutError::InvalidActionId(v)) if v == id
+            ));
+        }
+    }
+    #[test]
+    fn test_failed_rename_removes_temporary_file() {
+        let dir = std::env::temp_dir().join("test_failed_rename_removes_temporary_file");
+        let _ = std::fs::remove_dir_all(&dir);
+        // Renaming a file over a directory that is not empty fails.
+        let path = dir.join("Test.desktop");
+        std::fs::create_dir_all(path.join("child")).unwrap();
+        assert!(matches!(
+            save_shortcut_file(ShortcutFile::new("Test", "/usr/bin/ls"), &path),
+            Err(LinuxShortcutError::IoAt { path: at, .. }) if at == path
+        ));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+    #[test]
+    fn test_field_code_positions() {
+        let content = "[Desktop Entry]\nType=Application\nName=Test\nExec=env FOO=bar /usr/bin/app --bar %f -- %U\n";
+        let shortcut = parse_desktop_entry(content, ParseOptions::default()).unwrap();
+        assert_eq!(shortcut.arguments, vec!["--bar", "--"]);
+        assert_eq!(
+            shortcut.field_codes,
+            vec![(1, "%f".to_owned()), (2, "%U".to_owned())]
+        );
+        let rendered = render_desktop_entry(shortcut).unwrap();
+        assert!(rendered.contains("Exec=env FOO=bar /usr/bin/app --bar %f -- %U\n"));
+
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/app")
+            .field_code("%U")
+            .arg("--foo");
+        let rendered = render_desktop_entry(shortcut).unwrap();
+        assert!(rendered.contains("Exec=/usr/bin/app %U --foo\n"));
+    }
+    #[test]
+    fn test_extra_escaping() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .extra("X-Note", "first\nExec=/usr/bin/rm")
+            .extra("X-Note[de]", "zweite");
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains("X-Note=first\\nExec=/usr/bin/rm\n"));
+        assert_eq!(content.matches("Exec=").count(), 2);
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+        for key in [
+            "Exec",
+            "Name[de]",
+            "X Note",
+            "X-Note[]",
+            "[Desktop Action a]",
+        ] {
+            let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").extra(key, "value");
+            assert!(matches!(
+                render_desktop_entry(shortcut),
+                Err(LinuxShortcutError::InvalidExtraKey(v)) if v == key
+            ));
+        }
+        let hotkey = Hotkey::new(HotkeyKey::Char('P')).ctrl();
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .hotkey(hotkey)
+            .extra("X-KDE-Shortcuts", "Print");
+        assert!(render_desktop_entry(shortcut).is_err());
+    }
+    #[test]
+    fn test_exec_prefix_escaping() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/app").exec_prefix("env\nFOO=bar");
+        let content = render_desktop_entry(shortcut.clone()).unwrap();
+        assert!(content.contains("X-Exec-Prefix=env\\nFOO=bar\n"));
+        assert!(!content.lines().any(|line| line.starts_with("FOO=")));
+        assert_eq!(
+            shortcut,
+            parse_desktop_entry(&content, ParseOptions::default()).unwrap()
+        );
+    }
 }
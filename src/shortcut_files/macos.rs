@@ -0,0 +1,247 @@
+use super::{ParseOptions, ShortcutFile, ShortcutTarget};
+use std::{
+    fs::{OpenOptions, Permissions},
+    io::Write,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use thiserror::Error;
+#[derive(Debug, Error)]
+pub enum MacShortcutError {
+    #[error(transparent)]
+    IOErr(#[from] std::io::Error),
+    #[error("Path was not valid UTF-8")]
+    PathNotValidUTF8,
+    #[error("Missing Value: {0}")]
+    MissingValue(&'static str),
+    #[error("Shortcut target {0} is not supported by .command files")]
+    UnsupportedTarget(ShortcutTarget),
+}
+/// Saves a Shortcut as an executable `.command` script.
+///
+/// Finder always opens `.command` files in Terminal so `show_terminal` has no effect.
+/// The icon, categories and hotkey are ignored.
+pub fn save_shortcut_file(
+    shortcut: ShortcutFile,
+    to: impl AsRef<Path>,
+) -> Result<(), MacShortcutError> {
+    debug!(
+        "Creating Shortcut to {} at {:?}",
+        shortcut.target,
+        to.as_ref()
+    );
+    let ShortcutFile {
+        target,
+        description,
+        arguments,
+        working_directory,
+        env,
+        ..
+    } = shortcut;
+    // Checked before the file is opened so an unsupported shortcut leaves an existing file untouched.
+    let ShortcutTarget::Path(path) = target else {
+        return Err(MacShortcutError::UnsupportedTarget(target));
+    };
+    let command = path.to_str().ok_or(MacShortcutError::PathNotValidUTF8)?;
+    let working_directory = working_directory
+        .as_deref()
+        .map(|v| v.to_str().ok_or(MacShortcutError::PathNotValidUTF8))
+        .transpose()?;
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(to.as_ref())?;
+    let mut writer = std::io::BufWriter::new(file);
+    writeln!(writer, "#!/bin/sh")?;
+    if let Some(description) = description {
+        for line in description.lines() {
+            writeln!(writer, "# {}", line)?;
+        }
+    }
+    if let Some(working_directory) = working_directory {
+        writeln!(
+            writer,
+            "cd {} || exit 1",
+            quote_shell_argument(working_directory)
+        )?;
+    }
+    write!(writer, "exec")?;
+    if !env.is_empty() {
+        write!(writer, " env")?;
+        for (key, value) in &env {
+            write!(
+                writer,
+                " {}",
+                quote_shell_argument(&format!("{}={}", key, value))
+            )?;
+        }
+    }
+    write!(writer, " {}", quote_shell_argument(command))?;
+    for argument in &arguments {
+        write!(writer, " {}", quote_shell_argument(argument))?;
+    }
+    writeln!(writer)?;
+    writer.flush()?;
+    std::fs::set_permissions(to, Permissions::from_mode(0o755))?;
+    Ok(())
+}
+/// Reads a `.command` script created by [save_shortcut_file].
+///
+/// The name of the shortcut is taken from the file stem.
+pub fn read_shortcut_file(path: impl AsRef<Path>) -> Result<ShortcutFile, MacShortcutError> {
+    read_shortcut_file_with_options(path, ParseOptions::default())
+}
+/// In lenient mode a script without an `exec` line is read as a shortcut to an empty path.
+pub fn read_shortcut_file_with_options(
+    path: impl AsRef<Path>,
+    options: ParseOptions,
+) -> Result<ShortcutFile, MacShortcutError> {
+    let name = path
+        .as_ref()
+        .file_stem()
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let read = std::fs::read_to_string(path)?;
+    let mut description: Option<String> = None;
+    let mut working_directory = None;
+    let mut exec = None;
+    for line in read.lines() {
+        let line = line.trim();
+        if line.starts_with("#!") {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            match &mut description {
+                Some(description) => {
+                    description.push('\n');
+                    description.push_str(comment);
+                }
+                None => description = Some(comment.to_owned()),
+            }
+        } else if let Some(cd) = line.strip_prefix("cd ") {
+            let cd = cd.strip_suffix("|| exit 1").unwrap_or(cd);
+            working_directory = split_shell(cd).into_iter().next().map(PathBuf::from);
+        } else if let Some(command) = line.strip_prefix("exec ") {
+            exec = Some(split_shell(command));
+        }
+    }
+    let exec = match exec {
+        Some(exec) => exec,
+        None if options.lenient => vec![],
+        None => return Err(MacShortcutError::MissingValue("exec")),
+    };
+    let mut exec = exec.into_iter().peekable();
+    let mut env = vec![];
+    if exec.next_if_eq("env").is_some() {
+        while let Some(variable) = exec.next_if(|v| v.contains('=')) {
+            if let Some((key, value)) = variable.split_once('=') {
+                env.push((key.to_owned(), value.to_owned()));
+            }
+        }
+    }
+    let path = match exec.next() {
+        Some(path) => path,
+        None if options.lenient => String::new(),
+        None => return Err(MacShortcutError::MissingValue("Path")),
+    };
+    let shortcut = ShortcutFile {
+        name,
+        description,
+        target: ShortcutTarget::Path(PathBuf::from(path)),
+        arguments: exec.collect(),
+        working_directory,
+        env,
+        ..Default::default()
+    };
+    Ok(shortcut)
+}
+/// Wraps an argument in single quotes so the shell does not interpret it.
+fn quote_shell_argument(argument: &str) -> String {
+    format!("'{}'", argument.replace('\'', "'\\''"))
+}
+/// Splits a line of single quoted shell arguments.
+///
+/// Reverses [quote_shell_argument].
+fn split_shell(value: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut quoted = false;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                quoted = !quoted;
+                in_argument = true;
+            }
+            '\\' if !quoted => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                    in_argument = true;
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::{
+        quote_shell_argument, read_shortcut_file, save_shortcut_file, split_shell, MacShortcutError,
+    };
+    use crate::shortcut_files::ShortcutFile;
+
+    #[test]
+    fn test_split_shell() {
+        let quoted = quote_shell_argument("it's here");
+        assert_eq!(quoted, "'it'\\''s here'");
+        assert_eq!(split_shell(&quoted), vec!["it's here"]);
+    }
+    #[test]
+    fn test_save_shortcut_file() {
+        let shortcut = ShortcutFile::new("Test", "/bin/ls")
+            .description("This is a test shortcut")
+            .working_directory("/Users/Shared")
+            .arg("-l")
+            .arg("My Folder")
+            .env("GREETING", "Hello World");
+        let path = std::env::temp_dir().join("Test.command");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(shortcut, read_shortcut_file(path).unwrap());
+    }
+    #[test]
+    fn test_unsupported_target_keeps_file() {
+        let path = std::env::temp_dir().join("test_unsupported_target_keeps_file.command");
+        std::fs::write(&path, "#!/bin/sh\nexec /bin/ls\n").unwrap();
+        let shortcut = ShortcutFile::new_url("Test", "https://example.com");
+        assert!(matches!(
+            save_shortcut_file(shortcut, &path),
+            Err(MacShortcutError::UnsupportedTarget(_))
+        ));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "#!/bin/sh\nexec /bin/ls\n"
+        );
+    }
+}
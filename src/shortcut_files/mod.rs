@@ -1,6 +1,31 @@
 use cfg_if::cfg_if;
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
+mod action;
+mod builder;
+mod category;
+#[cfg(any(target_os = "linux", feature = "desktop"))]
+mod desktop_entry;
+mod hotkey;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+mod install;
+mod target;
+pub use action::*;
+pub use builder::*;
+pub use category::*;
+#[cfg(any(target_os = "linux", feature = "desktop"))]
+pub use desktop_entry::*;
+pub use hotkey::*;
+#[cfg(target_os = "linux")]
+pub use install::*;
+pub use target::*;
+#[doc(hidden)]
+pub mod url;
+use url::UrlShortcutError;
 
 cfg_if! {
     if #[cfg(target_os = "windows")] {
@@ -9,16 +34,21 @@ cfg_if! {
         use windows::*;
         type ErrorType = WindowsShortcutError;
     } else if #[cfg(target_os = "linux")] {
-        #[doc(hidden)]
-        pub mod linux;
         use linux::*;
         type ErrorType = LinuxShortcutError;
     } else if #[cfg(target_os = "macos")] {
-        compile_error!("MacOS is not supported yet.");
+        #[doc(hidden)]
+        pub mod macos;
+        use macos::*;
+        type ErrorType = MacShortcutError;
     }else {
         compile_error!("Unsupported OS");
     }
 }
+// Declared outside of the host dispatch so the `desktop` feature can use it on every host.
+#[cfg(any(target_os = "linux", feature = "desktop"))]
+#[cfg_attr(not(feature = "desktop"), doc(hidden))]
+pub mod linux;
 #[derive(Debug, Error)]
 pub enum FileShortcutError {
     /// Error creating the shortcut file.
@@ -32,6 +62,282 @@ pub enum FileShortcutError {
     IconPathDoesNotExist(PathBuf),
     #[error("Working Directory path does not exist.")]
     WorkingDirectoryPathDoesNotExist(PathBuf),
+    /// The format can not set environment variables.
+    #[error("{0:?} shortcuts do not support environment variables.")]
+    EnvNotSupported(ShortcutFormat),
+    /// A standard directory could not be found. Contains the environment variable that was checked.
+    #[error("Unable to find the {0} directory.")]
+    UnknownDirectory(String),
+    /// The file already exists. See [ShortcutFile::save_if_absent]
+    #[error("{0:?} already exists.")]
+    AlreadyExists(PathBuf),
+    /// The name is required by the format but was empty.
+    #[error("The name of the shortcut is empty.")]
+    EmptyName,
+    /// No path or URL was set. See [ShortcutFileBuilder::build]
+    #[error("The shortcut has no target.")]
+    MissingTarget,
+    /// Fields were set that can not be used together. Such as arguments for a URL.
+    #[error("Invalid combination: {0}")]
+    InvalidCombination(&'static str),
+    /// A field was set that the format can not store. See [WriteOptions::strict_platform]
+    #[error("{field} is not supported by {format:?} shortcuts.")]
+    UnsupportedOnPlatform {
+        field: &'static str,
+        format: ShortcutFormat,
+    },
+    /// An argument contains a field code that was removed from the Desktop Entry spec. Such as `%d`
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    #[error("Deprecated field code {0} in an argument.")]
+    DeprecatedFieldCode(String),
+    /// Error creating a `.desktop` file on a host that is not Linux.
+    #[cfg(all(feature = "desktop", not(target_os = "linux")))]
+    #[error(transparent)]
+    DesktopError(#[from] linux::LinuxShortcutError),
+    /// Error creating a `.url` file.
+    #[error(transparent)]
+    UrlError(#[from] UrlShortcutError),
+    /// The blocking task saving or reading the shortcut failed.
+    #[cfg(feature = "tokio")]
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+}
+/// The file format a shortcut is saved as.
+///
+/// | Format  | Available on                                  |
+/// |---------|-----------------------------------------------|
+/// | Lnk     | Windows                                       |
+/// | Desktop | Linux. Other hosts with the `desktop` feature |
+/// | Command | MacOS                                         |
+/// | Url     | All                                           |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ShortcutFormat {
+    /// A Windows Shell Link. `.lnk`
+    #[cfg(target_os = "windows")]
+    Lnk,
+    /// A Freedesktop Desktop Entry. `.desktop`
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    Desktop,
+    /// An executable shell script opened by Finder. `.command`
+    #[cfg(target_os = "macos")]
+    Command,
+    /// An Internet Shortcut pointing to the target as a `file://` URL. `.url`
+    Url,
+}
+impl ShortcutFormat {
+    /// The format used by [ShortcutFile::save] on the current host.
+    #[cfg(target_os = "windows")]
+    pub const NATIVE: Self = Self::Lnk;
+    /// The format used by [ShortcutFile::save] on the current host.
+    #[cfg(target_os = "linux")]
+    pub const NATIVE: Self = Self::Desktop;
+    /// The format used by [ShortcutFile::save] on the current host.
+    #[cfg(target_os = "macos")]
+    pub const NATIVE: Self = Self::Command;
+
+    /// The file extension of the format without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            #[cfg(target_os = "windows")]
+            Self::Lnk => "lnk",
+            #[cfg(any(target_os = "linux", feature = "desktop"))]
+            Self::Desktop => "desktop",
+            #[cfg(target_os = "macos")]
+            Self::Command => "command",
+            Self::Url => "url",
+        }
+    }
+    /// Appends the extension of the format if the path has none or a different one.
+    ///
+    /// `.directory` is also accepted for [ShortcutFormat::Desktop]. See [EntryType::Directory]
+    fn apply_extension(&self, path: PathBuf) -> PathBuf {
+        let extension = path.extension().and_then(|v| v.to_str());
+        if extension.is_some_and(|v| v.eq_ignore_ascii_case(self.extension())) {
+            return path;
+        }
+        #[cfg(any(target_os = "linux", feature = "desktop"))]
+        if *self == Self::Desktop && extension.is_some_and(|v| v.eq_ignore_ascii_case("directory"))
+        {
+            return path;
+        }
+        let mut path = path.into_os_string();
+        path.push(".");
+        path.push(self.extension());
+        path.into()
+    }
+}
+/// Options for [ShortcutFile::read_with_options]
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::ParseOptions;
+/// let options = ParseOptions::default().lenient();
+/// assert!(options.lenient);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Skip malformed lines and use defaults for missing required keys instead of failing.
+    pub lenient: bool,
+    /// What to do when a key appears twice in the same group of a `.desktop` file.
+    pub on_duplicate: DuplicateKeys,
+    /// Read the name and description of a `.desktop` file in this locale. Such as `de_DE.UTF-8`
+    ///
+    /// Falls back to the untranslated value if there is no translation.
+    pub locale: Option<String>,
+}
+impl ParseOptions {
+    /// Enables lenient parsing.
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+    /// Sets what to do when a key appears twice in the same group.
+    pub fn on_duplicate(mut self, on_duplicate: DuplicateKeys) -> Self {
+        self.on_duplicate = on_duplicate;
+        self
+    }
+    /// Reads the name and description in the given locale.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+    /// Reads the name and description in the locale of the current user.
+    ///
+    /// The locale is read from `$LC_ALL`, `$LC_MESSAGES` or `$LANG`.
+    pub fn current_locale(mut self) -> Self {
+        self.locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|v| !v.is_empty());
+        self
+    }
+}
+/// What to do when a key appears twice in the same group. See [ParseOptions::on_duplicate]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateKeys {
+    /// The last value is used.
+    #[default]
+    LastWins,
+    /// The first value is used. Later values are ignored.
+    FirstWins,
+    /// Fail with `LinuxShortcutError::DuplicateKey`
+    Error,
+}
+/// The line ending used when writing a `.desktop` file. See [WriteOptions::line_ending]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`. For tools that expect Windows line endings.
+    CrLf,
+}
+/// Options for [ShortcutFile::save_with_options]
+///
+/// # Example
+/// ```
+/// use shortcut_rs::shortcut_files::WriteOptions;
+/// let options = WriteOptions::default().trailing_semicolons(false);
+/// assert!(!options.trailing_semicolons);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct WriteOptions {
+    /// End list values of `.desktop` files such as `Categories` with a `;`
+    ///
+    /// The spec requires it so this defaults to `true`. Both styles are accepted when reading.
+    pub trailing_semicolons: bool,
+    /// The line ending of `.desktop` files. Defaults to [LineEnding::Lf]
+    ///
+    /// Both are accepted when reading.
+    pub line_ending: LineEnding,
+    /// Fail with [FileShortcutError::UnsupportedOnPlatform] instead of ignoring fields the format can not store.
+    ///
+    /// Such as categories in a `.lnk` file. Defaults to `false`.
+    /// The name is not checked as formats without one take it from the file name.
+    pub strict_platform: bool,
+    /// Replaces the name of the `[Desktop Entry]` group. Such as `KDE Desktop Entry`
+    ///
+    /// Only for tools that expect another group. The file can not be read by this crate.
+    pub group_name: Option<String>,
+    /// Skip keys of `.desktop` files that are equal to their default. Such as `Terminal=false`
+    ///
+    /// `Version` is only written if [ShortcutFile::desktop_spec_version] is set.
+    /// `Type` is required by the spec so it is always written. Defaults to `false`.
+    pub omit_defaults: bool,
+}
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            trailing_semicolons: true,
+            line_ending: LineEnding::default(),
+            strict_platform: false,
+            group_name: None,
+            omit_defaults: false,
+        }
+    }
+}
+impl WriteOptions {
+    /// Sets if list values end with a `;`
+    pub fn trailing_semicolons(mut self, trailing_semicolons: bool) -> Self {
+        self.trailing_semicolons = trailing_semicolons;
+        self
+    }
+    /// Sets the line ending of `.desktop` files.
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+    /// Fail instead of ignoring fields the format can not store.
+    pub fn strict_platform(mut self, strict_platform: bool) -> Self {
+        self.strict_platform = strict_platform;
+        self
+    }
+    /// Replaces the name of the `[Desktop Entry]` group.
+    pub fn group_name(mut self, group_name: impl Into<String>) -> Self {
+        self.group_name = Some(group_name.into());
+        self
+    }
+    /// Skip keys that are equal to their default.
+    pub fn omit_defaults(mut self, omit_defaults: bool) -> Self {
+        self.omit_defaults = omit_defaults;
+        self
+    }
+}
+/// The `Type` of a desktop entry.
+///
+/// Only used on Linux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EntryType {
+    /// Launches an executable.
+    Application,
+    /// Opens a URL.
+    Link,
+    /// A folder of the application menu. Saved as a `.directory` file with no `Exec` key.
+    Directory,
+}
+impl Display for EntryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Application => write!(f, "Application"),
+            Self::Link => write!(f, "Link"),
+            Self::Directory => write!(f, "Directory"),
+        }
+    }
+}
+/// The state of the window when the shortcut is launched.
+///
+/// Only supported on Windows. The Desktop Entry spec has no equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowState {
+    #[default]
+    Normal,
+    Minimized,
+    Maximized,
 }
 
 /// A builder for creating shortcut files.
@@ -47,70 +353,404 @@ pub enum FileShortcutError {
 ///
 /// ```
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct ShortcutFile {
     /// Name of the shortcut. Ignored on Windows.
     pub name: String,
     /// Description of the shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub description: Option<String>,
-    /// Path to executable.
-    pub path: PathBuf,
+    /// What the shortcut points to. Usually the path to an executable.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub target: ShortcutTarget,
     /// Arguments to pass to the executable.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub arguments: Vec<String>,
     /// Path to icon.
+    ///
+    /// On Linux, this can also be the name of an icon from the icon theme. Such as `firefox`.
+    /// A name has no directory and no image extension. It is not checked to exist.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub icon: Option<PathBuf>,
+    /// Index of the icon within the icon file.
+    ///
+    /// Only used on Windows where executables and DLLs can contain multiple icons.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub icon_index: i32,
     /// Working directory of the shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub working_directory: Option<PathBuf>,
     /// Whether to show the terminal or command prompt when running the shortcut
     ///
     /// Defaults to false.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub show_terminal: bool,
+    /// State of the window when the shortcut is launched.
+    ///
+    /// On Windows, this takes precedence over `show_terminal`.
+    /// On Linux, this is ignored and only `Terminal=true/false` is written.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub window_state: Option<WindowState>,
     /// Categories of the shortcut.
     ///
     /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     pub categories: Vec<String>,
-    // TODO: Add support for hotkeys
+    /// Global hotkey that launches the shortcut.
+    ///
+    /// On Linux, this is written as `X-KDE-Shortcuts` as the desktop entry spec has no standard key.
+    /// A value that is not a single hotkey, such as `Meta+Space,Ctrl+Alt+T`, is read into [extra](Self::extra).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub hotkey: Option<Hotkey>,
+    /// Whether the shortcut should always prompt to run as administrator.
+    ///
+    /// Only supported on Windows. Ignored on other platforms.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub run_as_admin: bool,
+    /// The `WM_CLASS` of the launched application. Used by taskbars to group its windows.
+    ///
+    /// Only used on Linux.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub startup_wm_class: Option<String>,
+    /// MIME types the shortcut can open.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub mime_types: Vec<String>,
+    /// Keywords used by application launchers when searching.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub keywords: Vec<String>,
+    /// Translations of the name keyed by locale. Such as `de` or `fr_CA`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub localized_names: BTreeMap<String, String>,
+    /// Translations of the description keyed by locale. Such as `de` or `fr_CA`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub localized_descriptions: BTreeMap<String, String>,
+    /// Generic name of the application. Such as `Web Browser`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub generic_name: Option<String>,
+    /// Whether the shortcut should be hidden from application menus.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub no_display: bool,
+    /// Whether the shortcut should be treated as deleted.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hidden: bool,
+    /// Desktop environments the shortcut should only be shown in. Such as `GNOME`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub only_show_in: Vec<String>,
+    /// Desktop environments the shortcut should not be shown in. Such as `KDE`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub not_show_in: Vec<String>,
+    /// Environment variables set before launching the executable.
+    ///
+    /// On Linux and MacOS, the command is prefixed with `env KEY=VALUE`.
+    /// Windows shortcuts can not set environment variables so saving a `.lnk` or `.url` fails with [FileShortcutError::EnvNotSupported].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub env: Vec<(String, String)>,
+    /// Keys that are not otherwise supported. Such as `X-GNOME-Autostart-enabled`.
+    ///
+    /// Unknown keys of a desktop file are kept here when reading and written after the other keys.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub extra: BTreeMap<String, String>,
+    /// Additional actions shown in the right-click menu of the launcher.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub actions: Vec<DesktopAction>,
+    /// Version of the Desktop Entry spec the file conforms to. Written as `Version`.
+    ///
+    /// `None` writes the latest supported version. See [DESKTOP_SPEC_VERSION]
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub desktop_spec_version: Option<String>,
+    /// If the launcher should show a busy cursor until the application starts. Written as `StartupNotify`.
+    ///
+    /// `None` leaves it up to the launcher.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub startup_notify: Option<bool>,
+    /// If the application is launched over D-Bus instead of `Exec`. Written as `DBusActivatable`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub dbus_activatable: Option<bool>,
+    /// If the application has a single main window. Written as `SingleMainWindow`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub single_main_window: Option<bool>,
+    /// If the application prefers to run on a more powerful discrete GPU. Written as `PrefersNonDefaultGPU`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub prefers_non_default_gpu: Option<bool>,
+    /// Executable that must exist for the shortcut to be shown. Written as `TryExec`.
+    ///
+    /// Unlike the target this is not checked to exist when saving.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub try_exec: Option<PathBuf>,
+    /// Written verbatim before the command of `Exec`. Such as `env GDK_BACKEND=x11`
+    ///
+    /// Not quoted or escaped so the caller must quote it following the Desktop Entry spec.
+    ///
+    /// The prefix is also written to the `X-Exec-Prefix` key. When reading, it is only removed from
+    /// `Exec` if its arguments match the first arguments of `Exec`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub exec_prefix: Option<String>,
+    /// Expand `~` and make relative paths absolute when saving.
+    ///
+    /// Applies to the target, icon and working directory.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub expand_paths: bool,
+    /// The `Type` of the desktop entry.
+    ///
+    /// `None` uses [EntryType::Link] for URLs and [EntryType::Application] otherwise.
+    /// The target of a [EntryType::Directory] is ignored.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub entry_type: Option<EntryType>,
+    /// Reverse DNS id of the application. Such as `com.example.MyApp`
+    ///
+    /// Used as the file name when installing into the applications menu and as the default `StartupWMClass`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub app_id: Option<String>,
+    /// D-Bus interfaces the application implements. Written as `Implements`.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub implements: Vec<String>,
+    /// Field codes such as `%U` in `Exec` with the index of the argument they are written before.
+    ///
+    /// The launcher replaces them with the files or URLs the application is opened with.
+    /// Field codes are read into this instead of the arguments. An index past the last argument
+    /// places the field code after every argument.
+    ///
+    /// On Windows, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub field_codes: Vec<(usize, String)>,
+    /// Tooltip of a Windows shortcut. Used instead of the description if set.
+    ///
+    /// The description is still written as `Comment` on Linux.
+    /// Reading a `.lnk` file sets the description as Windows stores only one value.
+    ///
+    /// On Linux, this is ignored.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub tooltip: Option<String>,
 }
+/// The version of the Desktop Entry spec written by default.
+pub const DESKTOP_SPEC_VERSION: &str = "1.5";
 
-impl Default for ShortcutFile {
-    fn default() -> Self {
-        Self {
-            name: String::new(),
-            description: None,
-            path: PathBuf::new(),
-            arguments: vec![],
-            icon: None,
-            working_directory: None,
-            show_terminal: false,
-            categories: vec![],
-        }
-    }
-}
 impl ShortcutFile {
     /// Creates a new shortcut file.
     pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
         Self {
             name: name.into(),
-            description: None,
-            path: path.into(),
-            arguments: vec![],
-            icon: None,
-            show_terminal: false,
-            categories: vec![],
-            working_directory: None,
+            target: ShortcutTarget::Path(path.into()),
+            ..Default::default()
+        }
+    }
+    /// Creates a new shortcut file pointing to a URL.
+    pub fn new_url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            target: ShortcutTarget::Url(url.into()),
+            ..Default::default()
+        }
+    }
+    /// Creates a new shortcut file pointing to a location in the Windows Shell namespace.
+    ///
+    /// See [ShortcutTarget::Shell]
+    pub fn new_shell(name: impl Into<String>, location: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            target: ShortcutTarget::Shell(location.into()),
+            ..Default::default()
         }
     }
+    /// Sets the path the shortcut points to. Replacing the current target.
+    pub fn target(mut self, path: impl Into<PathBuf>) -> Self {
+        self.target = ShortcutTarget::Path(path.into());
+        self
+    }
     /// Sets the description of the shortcut.
     pub fn description(mut self, description: impl Into<String>) -> Self {
         self.description = Some(description.into());
         self
     }
+    /// Removes the description of the shortcut.
+    pub fn clear_description(mut self) -> Self {
+        self.description = None;
+        self
+    }
+    /// Sets the tooltip of a Windows shortcut. Takes precedence over the description.
+    ///
+    /// Ignored on Linux.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+    /// Sets the generic name of the shortcut.
+    ///
+    /// Ignored on Windows.
+    pub fn generic_name(mut self, generic_name: impl Into<String>) -> Self {
+        self.generic_name = Some(generic_name.into());
+        self
+    }
+    /// Adds a translation of the name for the given locale.
+    pub fn localized_name(mut self, locale: impl Into<String>, name: impl Into<String>) -> Self {
+        self.localized_names.insert(locale.into(), name.into());
+        self
+    }
+    /// Adds a translation of the description for the given locale.
+    pub fn localized_description(
+        mut self,
+        locale: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.localized_descriptions
+            .insert(locale.into(), description.into());
+        self
+    }
     /// Sets the working directory of the shortcut.
     pub fn working_directory(mut self, working_directory: impl Into<PathBuf>) -> Self {
         self.working_directory = Some(working_directory.into());
         self
     }
+    /// Removes the working directory of the shortcut.
+    pub fn clear_working_directory(mut self) -> Self {
+        self.working_directory = None;
+        self
+    }
+    /// Sets the working directory to the directory containing the target.
+    ///
+    /// Does nothing if the target is not a path or the path has no parent. Such as `/` or `ls`.
+    pub fn working_directory_from_target(mut self) -> Self {
+        if let ShortcutTarget::Path(path) = &self.target {
+            if let Some(parent) = path.parent().filter(|v| !v.as_os_str().is_empty()) {
+                self.working_directory = Some(parent.to_path_buf());
+            }
+        }
+        self
+    }
     /// Adds an argument to the shortcut.
     pub fn arg(mut self, argument: impl Into<String>) -> Self {
         self.arguments.push(argument.into());
@@ -123,21 +763,203 @@ impl ShortcutFile {
         self.arguments = arguments;
         self
     }
+    /// Adds multiple arguments after the existing arguments.
+    pub fn extend_arguments(
+        mut self,
+        arguments: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.arguments.extend(arguments.into_iter().map(Into::into));
+        self
+    }
+    /// Splits a command line into arguments and adds them after the existing arguments.
+    ///
+    /// Uses POSIX shell quoting without expansion. Arguments are separated by whitespace.
+    /// Single quotes keep everything literally. Within double quotes `\` only escapes `"`, `\`, `$` and `` ` ``.
+    /// Outside of quotes `\` escapes any character.
+    ///
+    /// ```
+    /// use shortcut_rs::shortcut_files::ShortcutFile;
+    /// let shortcut = ShortcutFile::new("Test", "/usr/bin/app")
+    ///     .args_from_command_line(r#"--name "John Doe" --flag"#);
+    /// assert_eq!(shortcut.arguments, vec!["--name", "John Doe", "--flag"]);
+    /// ```
+    pub fn args_from_command_line(mut self, command_line: &str) -> Self {
+        self.arguments.extend(split_command_line(command_line));
+        self
+    }
+    /// Removes all arguments from the shortcut.
+    pub fn clear_arguments(mut self) -> Self {
+        self.arguments.clear();
+        self
+    }
+    /// Adds an environment variable to set before launching the executable.
+    ///
+    /// Not supported on Windows.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+    /// Adds a key that is not otherwise supported. Such as `X-GNOME-Autostart-enabled`.
+    ///
+    /// The value is escaped like `Name`. Keys written from other fields such as `Exec` fail to save.
+    ///
+    /// Ignored on Windows.
+    pub fn extra(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra.insert(key.into(), value.into());
+        self
+    }
+    /// Adds an action to the right-click menu of the launcher.
+    ///
+    /// Ignored on Windows.
+    pub fn action(mut self, action: DesktopAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+    /// Sets the version of the Desktop Entry spec. Defaults to [DESKTOP_SPEC_VERSION]
+    ///
+    /// Ignored on Windows.
+    pub fn desktop_spec_version(mut self, version: impl Into<String>) -> Self {
+        self.desktop_spec_version = Some(version.into());
+        self
+    }
     /// Sets the icon of the shortcut.
+    ///
+    /// On Linux, this can be the name of an icon from the icon theme. Such as `firefox`.
+    /// Windows has no icon theme so the icon must be a path.
     pub fn icon(mut self, icon: impl Into<PathBuf>) -> Self {
         self.icon = Some(icon.into());
         self
     }
+    /// Removes the icon of the shortcut.
+    pub fn clear_icon(mut self) -> Self {
+        self.icon = None;
+        self
+    }
+    /// Sets the index of the icon within the icon file.
+    ///
+    /// Ignored on Linux.
+    pub fn icon_index(mut self, index: i32) -> Self {
+        self.icon_index = index;
+        self
+    }
+    /// Uses the first icon of the target executable as the icon.
+    ///
+    /// Does nothing if the target is not a path. Such as a URL.
+    pub fn icon_from_target(mut self) -> Self {
+        if let ShortcutTarget::Path(path) = &self.target {
+            self.icon = Some(path.clone());
+            self.icon_index = 0;
+        }
+        self
+    }
+    /// Expands a leading `~` to the home directory and makes relative paths absolute when saving.
+    ///
+    /// The home directory is read from `$HOME`. On Windows `%USERPROFILE%` is used.
+    /// Relative paths are resolved against the current directory.
+    pub fn expand_paths(mut self) -> Self {
+        self.expand_paths = true;
+        self
+    }
+    /// Sets the `Type` of the desktop entry.
+    ///
+    /// Ignored on Windows.
+    pub fn entry_type(mut self, entry_type: EntryType) -> Self {
+        self.entry_type = Some(entry_type);
+        self
+    }
+    /// Sets the reverse DNS id of the application.
+    ///
+    /// Ignored on Windows.
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+    /// Adds a D-Bus interface the application implements. Such as `org.freedesktop.FileManager1`
+    ///
+    /// Ignored on Windows.
+    pub fn implements(mut self, interface: impl Into<String>) -> Self {
+        self.implements.push(interface.into());
+        self
+    }
+    /// Adds a field code such as `%U` to `Exec` after the arguments added so far.
+    ///
+    /// Ignored on Windows.
+    pub fn field_code(mut self, field_code: impl Into<String>) -> Self {
+        self.field_codes
+            .push((self.arguments.len(), field_code.into()));
+        self
+    }
     /// Sets the show command of the shortcut.
     pub fn show_terminal(mut self) -> Self {
         self.show_terminal = true;
         self
     }
+    /// Sets if the shortcut is launched in a terminal.
+    ///
+    /// Same as [ShortcutFile::show_terminal] but the value can be computed.
+    pub fn show_terminal_value(mut self, show_terminal: bool) -> Self {
+        self.show_terminal = show_terminal;
+        self
+    }
+    /// Sets the state of the window when the shortcut is launched.
+    ///
+    /// Ignored on Linux.
+    pub fn window_state(mut self, state: WindowState) -> Self {
+        self.window_state = Some(state);
+        self
+    }
+    /// Hides the shortcut from application menus. `NoDisplay=true`
+    ///
+    /// Ignored on Windows.
+    pub fn no_display(mut self) -> Self {
+        self.no_display = true;
+        self
+    }
+    /// Marks the shortcut as deleted. `Hidden=true`
+    ///
+    /// Ignored on Windows.
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+    /// Adds a desktop environment the shortcut should only be shown in.
+    pub fn only_show_in(mut self, desktop: impl Into<String>) -> Self {
+        self.only_show_in.push(desktop.into());
+        self
+    }
+    /// Sets the desktop environments the shortcut should only be shown in.
+    ///
+    /// # Warning
+    /// This will overwrite any existing desktop environments.
+    pub fn only_show_in_all(mut self, desktops: Vec<String>) -> Self {
+        self.only_show_in = desktops;
+        self
+    }
+    /// Adds a desktop environment the shortcut should not be shown in.
+    pub fn not_show_in(mut self, desktop: impl Into<String>) -> Self {
+        self.not_show_in.push(desktop.into());
+        self
+    }
+    /// Sets the desktop environments the shortcut should not be shown in.
+    ///
+    /// # Warning
+    /// This will overwrite any existing desktop environments.
+    pub fn not_show_in_all(mut self, desktops: Vec<String>) -> Self {
+        self.not_show_in = desktops;
+        self
+    }
     /// Adds a category to the shortcut.
+    ///
+    /// Custom categories should start with `X-`. See [ShortcutFile::category_enum] for the main categories.
     pub fn category(mut self, category: impl Into<String>) -> Self {
         self.categories.push(category.into());
         self
     }
+    /// Adds one of the main categories of the freedesktop menu spec.
+    pub fn category_enum(mut self, category: Category) -> Self {
+        self.categories.push(category.as_str().to_owned());
+        self
+    }
     /// Adds multiple categories to the shortcut.
     ///
     /// # Warning
@@ -146,30 +968,984 @@ impl ShortcutFile {
         self.categories = categories;
         self
     }
-    /// Saves the shortcut to the given path.
-    pub fn save(self, to: impl Into<PathBuf>) -> Result<(), FileShortcutError> {
-        if !self.path.exists() {
-            return Err(FileShortcutError::TargetPathDoesNotExist(self.path));
-        }
-        if let Some(icon) = &self.icon {
-            if !icon.exists() {
-                return Err(FileShortcutError::IconPathDoesNotExist(icon.clone()));
-            }
-        }
-        if let Some(working_directory) = &self.working_directory {
-            if !working_directory.exists() {
-                return Err(FileShortcutError::WorkingDirectoryPathDoesNotExist(
-                    working_directory.clone(),
-                ));
-            }
-        }
-
-        save_shortcut_file(self, to.into()).map_err(FileShortcutError::from)
+    /// Adds multiple categories after the existing categories.
+    pub fn extend_categories(
+        mut self,
+        categories: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.categories
+            .extend(categories.into_iter().map(Into::into));
+        self
     }
-    pub fn read(path: impl Into<PathBuf>) -> Result<Self, FileShortcutError> {
-        read_shortcut_file(path.into()).map_err(FileShortcutError::from)
+    /// Adds a MIME type to the shortcut.
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_types.push(mime_type.into());
+        self
     }
-}
+    /// Adds multiple MIME types to the shortcut.
+    ///
+    /// # Warning
+    /// This will overwrite any existing MIME types.
+    pub fn mime_types(mut self, mime_types: Vec<String>) -> Self {
+        self.mime_types = mime_types;
+        self
+    }
+    /// Adds a keyword to the shortcut.
+    pub fn keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+    /// Adds multiple keywords to the shortcut.
+    ///
+    /// # Warning
+    /// This will overwrite any existing keywords.
+    pub fn keywords(mut self, keywords: Vec<String>) -> Self {
+        self.keywords = keywords;
+        self
+    }
+    /// Sets the hotkey of the shortcut.
+    pub fn hotkey(mut self, combo: Hotkey) -> Self {
+        self.hotkey = Some(combo);
+        self
+    }
+    /// Makes the shortcut always run as administrator.
+    ///
+    /// Ignored on platforms other than Windows.
+    pub fn run_as_admin(mut self) -> Self {
+        self.run_as_admin = true;
+        self
+    }
+    /// Sets if the launcher should show a busy cursor until the application starts.
+    ///
+    /// Ignored on Windows.
+    pub fn startup_notify(mut self, startup_notify: bool) -> Self {
+        self.startup_notify = Some(startup_notify);
+        self
+    }
+    /// Sets if the application is launched over D-Bus.
+    ///
+    /// The file name must be the D-Bus name of the application. See [ShortcutFile::app_id]
+    ///
+    /// Ignored on Windows.
+    pub fn dbus_activatable(mut self, dbus_activatable: bool) -> Self {
+        self.dbus_activatable = Some(dbus_activatable);
+        self
+    }
+    /// Sets if the application has a single main window so launchers do not offer to open a new one.
+    ///
+    /// Ignored on Windows.
+    pub fn single_main_window(mut self, single_main_window: bool) -> Self {
+        self.single_main_window = Some(single_main_window);
+        self
+    }
+    /// Sets if the application prefers to run on a more powerful discrete GPU.
+    ///
+    /// Ignored on Windows.
+    pub fn prefers_non_default_gpu(mut self, prefers_non_default_gpu: bool) -> Self {
+        self.prefers_non_default_gpu = Some(prefers_non_default_gpu);
+        self
+    }
+    /// Sets a prefix written verbatim before the command of `Exec`. Such as `env GDK_BACKEND=x11`
+    ///
+    /// The prefix is not quoted or escaped. Quoting it correctly is up to the caller.
+    ///
+    /// Ignored on Windows.
+    pub fn exec_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.exec_prefix = Some(prefix.into());
+        self
+    }
+    /// Sets the executable that must exist for the shortcut to be shown.
+    ///
+    /// Ignored on Windows.
+    pub fn try_exec(mut self, path: impl Into<PathBuf>) -> Self {
+        self.try_exec = Some(path.into());
+        self
+    }
+    /// Sets the `StartupWMClass` of the shortcut.
+    ///
+    /// Ignored on Windows.
+    pub fn startup_wm_class(mut self, class: impl Into<String>) -> Self {
+        self.startup_wm_class = Some(class.into());
+        self
+    }
+    /// Saves the shortcut to the given path.
+    ///
+    /// Uses the native format of the host. See [ShortcutFormat::NATIVE]
+    ///
+    /// On Windows, shortcuts to a URL are saved as a `.url` file.
+    ///
+    /// Returns the path that was written. See [ShortcutFile::save_as]
+    pub fn save(self, to: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        self.save_as(to, format)
+    }
+    /// Same as [ShortcutFile::save] but with options for how the file is written.
+    pub fn save_with_options(
+        self,
+        to: impl Into<PathBuf>,
+        options: WriteOptions,
+    ) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        self.write(format.apply_extension(to.into()), format, options)
+    }
+    /// Saves the shortcut into the given directory.
+    ///
+    /// The file name is the name of the shortcut with path separators and characters
+    /// not allowed in file names replaced by `_`. Followed by the extension of the format.
+    pub fn save_to_dir(self, dir: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let file_name = sanitize_file_name(&self.name);
+        if file_name.is_empty() {
+            return Err(FileShortcutError::EmptyName);
+        }
+        let format = self.native_format();
+        let extension = match self.entry_type {
+            Some(EntryType::Directory) => "directory",
+            _ => format.extension(),
+        };
+        let to = dir.into().join(format!("{}.{}", file_name, extension));
+        self.save_as(to, format)
+    }
+    /// Saves the shortcut only if no file exists at the path.
+    ///
+    /// Fails with [FileShortcutError::AlreadyExists] instead of overwriting. Same as [ShortcutFile::save] otherwise.
+    pub fn save_if_absent(self, to: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        let to = format.apply_extension(to.into());
+        if to.exists() {
+            return Err(FileShortcutError::AlreadyExists(to));
+        }
+        let temporary = temporary_path(&to);
+        self.write(temporary.clone(), format, WriteOptions::default())?;
+        // Linking never replaces a file created at the path after it was checked.
+        let linked = std::fs::hard_link(&temporary, &to);
+        let _ = std::fs::remove_file(&temporary);
+        match linked {
+            Ok(()) => Ok(to),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err(FileShortcutError::AlreadyExists(to))
+            }
+            Err(err) => Err(ErrorType::from(err).into()),
+        }
+    }
+    /// Saves the shortcut to exactly the given path.
+    ///
+    /// Same as [ShortcutFile::save] but the extension of the path is never changed.
+    pub fn save_exact(self, to: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        self.write(to.into(), format, WriteOptions::default())
+    }
+    /// Saves the shortcut without checking the target, icon and working directory exist.
+    ///
+    /// Useful when creating shortcuts for another machine.
+    /// The shortcut may be broken on the current machine.
+    ///
+    /// Same as [ShortcutFile::save] otherwise.
+    pub fn save_unchecked(self, to: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        self.write_unchecked(
+            format.apply_extension(to.into()),
+            format,
+            WriteOptions::default(),
+        )
+    }
+    /// Saves the shortcut to the given path using the given format.
+    ///
+    /// Control characters other than newlines, tabs and carriage returns are removed from the name.
+    /// Those are escaped in `.desktop` files.
+    ///
+    /// If the path has no extension or a different one, the extension of the format is appended.
+    /// The path that was written is returned.
+    ///
+    /// See [ShortcutFormat] for the formats available on each host.
+    pub fn save_as(
+        self,
+        to: impl Into<PathBuf>,
+        format: ShortcutFormat,
+    ) -> Result<PathBuf, FileShortcutError> {
+        self.write(
+            format.apply_extension(to.into()),
+            format,
+            WriteOptions::default(),
+        )
+    }
+    fn native_format(&self) -> ShortcutFormat {
+        match self.target {
+            ShortcutTarget::Url(_) if cfg!(target_os = "windows") => ShortcutFormat::Url,
+            _ => ShortcutFormat::NATIVE,
+        }
+    }
+    /// Renders the shortcut as the content of a `.desktop` file without writing it.
+    ///
+    /// The name is sanitized the same way as [ShortcutFile::save_as] but paths are not checked to exist.
+    ///
+    /// `.lnk` files are binary and created by the Windows Shell so they can not be rendered.
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    pub fn render(&self) -> Result<String, FileShortcutError> {
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer)?;
+        // Paths are checked to be UTF-8 and every other value is a String so this never fails.
+        Ok(String::from_utf8(buffer).expect("every value written is UTF-8"))
+    }
+    /// Writes the shortcut as a `.desktop` file to any writer. Such as an in-memory buffer.
+    ///
+    /// Same as [ShortcutFile::render] but writes instead of returning a String.
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    pub fn write_to(&self, writer: impl std::io::Write) -> Result<(), FileShortcutError> {
+        let mut shortcut = self.clone();
+        shortcut.expand();
+        shortcut.prepare(ShortcutFormat::Desktop)?;
+        Ok(linux::write_desktop_entry(shortcut, writer)?)
+    }
+    /// Creates the Windows Shell Link of the shortcut without saving it.
+    ///
+    /// For setting properties that are not supported by [ShortcutFile]. Such as `SetRelativePath`.
+    /// Save it with `IPersistFile::Save`.
+    ///
+    /// # COM
+    /// COM must be initialized on the calling thread with `CoInitializeEx` before calling this.
+    /// It must stay initialized until the Shell Link is dropped and the Shell Link must only be used
+    /// from the apartment that created it.
+    #[cfg(target_os = "windows")]
+    pub fn build_shell_link(
+        &self,
+    ) -> Result<::windows::Win32::UI::Shell::IShellLinkW, WindowsShortcutError> {
+        let mut shortcut = self.clone();
+        shortcut.expand();
+        windows::build_shell_link(&shortcut)
+    }
+    /// Async version of [ShortcutFile::save].
+    ///
+    /// `.desktop` files are written with `tokio::fs`. Other formats are saved on a blocking thread.
+    #[cfg(feature = "tokio")]
+    pub async fn save_async(self, to: impl Into<PathBuf>) -> Result<PathBuf, FileShortcutError> {
+        let format = self.native_format();
+        let to = format.apply_extension(to.into());
+        #[cfg(target_os = "linux")]
+        {
+            let mut shortcut = self;
+            shortcut.expand();
+            shortcut.validate().map_err(|mut errors| errors.remove(0))?;
+            shortcut.prepare(format)?;
+            linux::save_shortcut_file_async(shortcut, &to).await?;
+            Ok(to)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            tokio::task::spawn_blocking(move || self.write(to, format, WriteOptions::default()))
+                .await?
+        }
+    }
+    fn write(
+        self,
+        to: PathBuf,
+        format: ShortcutFormat,
+        options: WriteOptions,
+    ) -> Result<PathBuf, FileShortcutError> {
+        self.write_with_checks(to, format, options, true)
+    }
+    fn write_unchecked(
+        self,
+        to: PathBuf,
+        format: ShortcutFormat,
+        options: WriteOptions,
+    ) -> Result<PathBuf, FileShortcutError> {
+        self.write_with_checks(to, format, options, false)
+    }
+    /// Expands the paths once and checks they exist if `check_paths` is set before writing.
+    fn write_with_checks(
+        mut self,
+        to: PathBuf,
+        format: ShortcutFormat,
+        options: WriteOptions,
+        check_paths: bool,
+    ) -> Result<PathBuf, FileShortcutError> {
+        self.expand();
+        if check_paths {
+            self.validate().map_err(|mut errors| errors.remove(0))?;
+        }
+        self.prepare(format)?;
+        if options.strict_platform {
+            if let Some(field) = self.unsupported_field(format) {
+                return Err(FileShortcutError::UnsupportedOnPlatform { field, format });
+            }
+        }
+        match format {
+            #[cfg(target_os = "windows")]
+            ShortcutFormat::Lnk => windows::save_shortcut_file(self, &to)?,
+            #[cfg(any(target_os = "linux", feature = "desktop"))]
+            ShortcutFormat::Desktop => linux::save_shortcut_file_with_options(self, &to, options)?,
+            #[cfg(target_os = "macos")]
+            ShortcutFormat::Command => macos::save_shortcut_file(self, &to)?,
+            ShortcutFormat::Url => url::save_shortcut_file(self, &to)?,
+        }
+        Ok(to)
+    }
+    /// Returns the first field that is set but is ignored by the format.
+    fn unsupported_field(&self, format: ShortcutFormat) -> Option<&'static str> {
+        let fields: Vec<(&'static str, bool)> = match format {
+            #[cfg(target_os = "windows")]
+            ShortcutFormat::Lnk => self.desktop_only_fields().to_vec(),
+            #[cfg(any(target_os = "linux", feature = "desktop"))]
+            ShortcutFormat::Desktop => vec![
+                ("tooltip", self.tooltip.is_some()),
+                ("icon_index", self.icon_index != 0),
+                ("run_as_admin", self.run_as_admin),
+                ("window_state", self.window_state.is_some()),
+            ],
+            #[cfg(target_os = "macos")]
+            ShortcutFormat::Command => {
+                let mut fields = self.desktop_only_fields().to_vec();
+                fields.extend([
+                    ("tooltip", self.tooltip.is_some()),
+                    ("icon", self.icon.is_some()),
+                    ("icon_index", self.icon_index != 0),
+                    ("hotkey", self.hotkey.is_some()),
+                    ("run_as_admin", self.run_as_admin),
+                    ("window_state", self.window_state.is_some()),
+                ]);
+                fields
+            }
+            ShortcutFormat::Url => {
+                let mut fields = self.desktop_only_fields().to_vec();
+                fields.extend([
+                    ("description", self.description.is_some()),
+                    ("tooltip", self.tooltip.is_some()),
+                    ("arguments", !self.arguments.is_empty()),
+                    ("working_directory", self.working_directory.is_some()),
+                    ("show_terminal", self.show_terminal),
+                    ("hotkey", self.hotkey.is_some()),
+                    ("run_as_admin", self.run_as_admin),
+                    ("window_state", self.window_state.is_some()),
+                ]);
+                fields
+            }
+        };
+        fields
+            .into_iter()
+            .find(|(_, is_set)| *is_set)
+            .map(|(field, _)| field)
+    }
+    /// Fields that are only stored by `.desktop` files and if they are set.
+    fn desktop_only_fields(&self) -> [(&'static str, bool); 24] {
+        [
+            ("categories", !self.categories.is_empty()),
+            ("startup_wm_class", self.startup_wm_class.is_some()),
+            ("mime_types", !self.mime_types.is_empty()),
+            ("keywords", !self.keywords.is_empty()),
+            ("localized_names", !self.localized_names.is_empty()),
+            (
+                "localized_descriptions",
+                !self.localized_descriptions.is_empty(),
+            ),
+            ("generic_name", self.generic_name.is_some()),
+            ("no_display", self.no_display),
+            ("hidden", self.hidden),
+            ("only_show_in", !self.only_show_in.is_empty()),
+            ("not_show_in", !self.not_show_in.is_empty()),
+            ("extra", !self.extra.is_empty()),
+            ("actions", !self.actions.is_empty()),
+            ("desktop_spec_version", self.desktop_spec_version.is_some()),
+            ("startup_notify", self.startup_notify.is_some()),
+            ("dbus_activatable", self.dbus_activatable.is_some()),
+            ("single_main_window", self.single_main_window.is_some()),
+            (
+                "prefers_non_default_gpu",
+                self.prefers_non_default_gpu.is_some(),
+            ),
+            ("try_exec", self.try_exec.is_some()),
+            ("exec_prefix", self.exec_prefix.is_some()),
+            ("entry_type", self.entry_type.is_some()),
+            ("app_id", self.app_id.is_some()),
+            ("implements", !self.implements.is_empty()),
+            ("field_codes", !self.field_codes.is_empty()),
+        ]
+    }
+    /// Expands the paths if [ShortcutFile::expand_paths] is set.
+    fn expand(&mut self) {
+        if !self.expand_paths {
+            return;
+        }
+        if let ShortcutTarget::Path(path) = &mut self.target {
+            *path = expand_path(path);
+        }
+        if let Some(icon) = self.icon.as_mut().filter(|v| !is_icon_name(v)) {
+            *icon = expand_path(icon);
+        }
+        if let Some(working_directory) = &mut self.working_directory {
+            *working_directory = expand_path(working_directory);
+        }
+    }
+    /// Compares two shortcuts after normalizing their paths.
+    ///
+    /// `/` and `\` are treated as the same separator. On Windows paths are compared case-insensitively.
+    /// Applies to the target, icon and working directory. Otherwise the same as `==`.
+    pub fn semantic_eq(&self, other: &Self) -> bool {
+        self.clone().normalize_paths() == other.clone().normalize_paths()
+    }
+    /// Hashes the content of the shortcut for detecting changes. Such as skipping a save when nothing changed.
+    ///
+    /// Shortcuts that are [ShortcutFile::semantic_eq] have the same fingerprint.
+    /// The value is the same between runs but may change with a new version of Rust or this crate.
+    /// Not cryptographic so it must not be used to verify untrusted files.
+    pub fn fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.clone().normalize_paths().hash(&mut hasher);
+        hasher.finish()
+    }
+    /// Normalizes the paths for [ShortcutFile::semantic_eq]
+    fn normalize_paths(mut self) -> Self {
+        if let ShortcutTarget::Path(path) = &mut self.target {
+            *path = normalize_path(path);
+        }
+        self.icon = self.icon.as_deref().map(normalize_path);
+        self.working_directory = self.working_directory.as_deref().map(normalize_path);
+        self
+    }
+    /// Checks that no fields are set that can not be used together.
+    fn check_combination(&self) -> Result<(), FileShortcutError> {
+        let is_url = matches!(self.target, ShortcutTarget::Url(_));
+        let error = match self.entry_type {
+            Some(EntryType::Directory) if !self.arguments.is_empty() => {
+                "a Directory entry can not have arguments"
+            }
+            Some(EntryType::Application) if is_url => "an Application entry requires a path target",
+            Some(EntryType::Link) if !is_url => "a Link entry requires a URL target",
+            _ if is_url && !self.arguments.is_empty() => "a URL target can not have arguments",
+            _ => return Ok(()),
+        };
+        Err(FileShortcutError::InvalidCombination(error))
+    }
+    /// Sanitizes the name and checks the shortcut can be saved in the given format.
+    fn prepare(&mut self, format: ShortcutFormat) -> Result<(), FileShortcutError> {
+        self.check_combination()?;
+        // Newlines and tabs are escaped in `.desktop` files.
+        self.name
+            .retain(|c| !c.is_control() || matches!(c, '\n' | '\t' | '\r'));
+        #[cfg(any(target_os = "linux", feature = "desktop"))]
+        if format == ShortcutFormat::Desktop {
+            if self.name.trim().is_empty() {
+                return Err(FileShortcutError::EmptyName);
+            }
+            if let Some(code) = self
+                .arguments
+                .iter()
+                .chain(self.field_codes.iter().map(|(_, v)| v))
+                .find_map(|v| linux::find_deprecated_field_code(v))
+            {
+                return Err(FileShortcutError::DeprecatedFieldCode(code));
+            }
+        }
+        #[cfg(target_os = "windows")]
+        let env_supported = !matches!(format, ShortcutFormat::Lnk | ShortcutFormat::Url);
+        #[cfg(not(target_os = "windows"))]
+        let env_supported = !matches!(format, ShortcutFormat::Url);
+        if !self.env.is_empty() && !env_supported {
+            return Err(FileShortcutError::EnvNotSupported(format));
+        }
+        Ok(())
+    }
+    /// Checks that the target, icon and working directory exist.
+    ///
+    /// Also fails with [FileShortcutError::InvalidCombination] if fields are set that can not be used together.
+    ///
+    /// Unlike [ShortcutFile::save] every failed check is returned.
+    /// Categories that are not registered by the freedesktop menu spec are logged as a warning.
+    pub fn validate(&self) -> Result<(), Vec<FileShortcutError>> {
+        let mut errors = vec![];
+        if let Err(err) = self.check_combination() {
+            errors.push(err);
+        }
+        errors.extend(self.broken_reasons());
+        for category in &self.categories {
+            if !Category::is_registered(category) {
+                log::warn!(
+                    "Category {} is not registered. Custom categories should start with X-",
+                    category
+                );
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    /// Checks if the target path exists.
+    ///
+    /// Always `true` for targets that are not a path such as URLs.
+    /// Use [ShortcutFile::broken_reasons] to also check the icon and working directory.
+    pub fn is_target_valid(&self) -> bool {
+        !self
+            .broken_reasons()
+            .iter()
+            .any(|v| matches!(v, FileShortcutError::TargetPathDoesNotExist(_)))
+    }
+    /// Returns every path of the shortcut that does not exist.
+    ///
+    /// Checks the target, icon and working directory. Used to find shortcuts that no longer work.
+    pub fn broken_reasons(&self) -> Vec<FileShortcutError> {
+        let mut errors = vec![];
+        let has_target = self.entry_type != Some(EntryType::Directory);
+        if let ShortcutTarget::Path(path) = &self.target {
+            if has_target && !path.exists() {
+                errors.push(FileShortcutError::TargetPathDoesNotExist(path.clone()));
+            }
+        }
+        if let Some(icon) = self.icon.as_ref().filter(|v| !is_icon_name(v)) {
+            if !icon.exists() {
+                errors.push(FileShortcutError::IconPathDoesNotExist(icon.clone()));
+            }
+        }
+        if let Some(working_directory) = &self.working_directory {
+            if !working_directory.exists() {
+                errors.push(FileShortcutError::WorkingDirectoryPathDoesNotExist(
+                    working_directory.clone(),
+                ));
+            }
+        }
+        errors
+    }
+    /// Reads a shortcut file in the native format of the host.
+    ///
+    /// Malformed files are rejected. See [ShortcutFile::read_with_options]
+    ///
+    /// The target, icon and working directory are never checked to exist.
+    /// Use [ShortcutFile::save_unchecked] to save a shortcut that was made on another machine.
+    pub fn read(path: impl Into<PathBuf>) -> Result<Self, FileShortcutError> {
+        Self::read_with_options(path, ParseOptions::default())
+    }
+    /// Reads a shortcut file using the given options.
+    ///
+    /// Files with the `.url` extension are read as Internet Shortcuts on every host.
+    pub fn read_with_options(
+        path: impl Into<PathBuf>,
+        options: ParseOptions,
+    ) -> Result<Self, FileShortcutError> {
+        let path = path.into();
+        if is_url_file(&path) {
+            return url::read_shortcut_file_with_options(path, options)
+                .map_err(FileShortcutError::from);
+        }
+        read_shortcut_file_with_options(path, options).map_err(FileShortcutError::from)
+    }
+    /// Reads a `.desktop` file from any reader. Such as an in-memory buffer or a file inside an archive.
+    ///
+    /// The content must be UTF-8.
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    pub fn read_from(reader: impl std::io::Read) -> Result<Self, FileShortcutError> {
+        linux::read_desktop_entry(reader, ParseOptions::default()).map_err(FileShortcutError::from)
+    }
+    /// Async version of [ShortcutFile::read].
+    ///
+    /// `.desktop` files are read with `tokio::fs`. Other formats are read on a blocking thread.
+    #[cfg(feature = "tokio")]
+    pub async fn read_async(path: impl Into<PathBuf>) -> Result<Self, FileShortcutError> {
+        let path = path.into();
+        #[cfg(target_os = "linux")]
+        if !is_url_file(&path) {
+            return linux::read_shortcut_file_async(path)
+                .await
+                .map_err(FileShortcutError::from);
+        }
+        tokio::task::spawn_blocking(move || Self::read(path)).await?
+    }
+}
+/// Setters for editing a shortcut behind a `&mut` reference. Such as one returned by [ShortcutFile::read].
+impl ShortcutFile {
+    /// Sets the name of the shortcut.
+    pub fn set_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = name.into();
+        self
+    }
+    /// Sets the path the shortcut points to. Replacing the current target.
+    pub fn set_target(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.target = ShortcutTarget::Path(path.into());
+        self
+    }
+    /// Sets the description of the shortcut.
+    pub fn set_description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.description = Some(description.into());
+        self
+    }
+    /// Sets the working directory of the shortcut.
+    pub fn set_working_directory(&mut self, working_directory: impl Into<PathBuf>) -> &mut Self {
+        self.working_directory = Some(working_directory.into());
+        self
+    }
+    /// Sets the arguments of the shortcut. Replacing the existing arguments.
+    pub fn set_arguments(
+        &mut self,
+        arguments: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.arguments = arguments.into_iter().map(Into::into).collect();
+        self
+    }
+    /// Sets the icon of the shortcut.
+    pub fn set_icon(&mut self, icon: impl Into<PathBuf>) -> &mut Self {
+        self.icon = Some(icon.into());
+        self
+    }
+    /// Sets the index of the icon within the icon file.
+    ///
+    /// Ignored on Linux.
+    pub fn set_icon_index(&mut self, index: i32) -> &mut Self {
+        self.icon_index = index;
+        self
+    }
+    /// Sets if the shortcut is launched in a terminal.
+    pub fn set_show_terminal(&mut self, show_terminal: bool) -> &mut Self {
+        self.show_terminal = show_terminal;
+        self
+    }
+    /// Sets the state of the window when the shortcut is launched.
+    ///
+    /// Ignored on Linux.
+    pub fn set_window_state(&mut self, state: WindowState) -> &mut Self {
+        self.window_state = Some(state);
+        self
+    }
+}
+/// Builders that only set a field if the value is `Some`. Such as when building a shortcut from a config file.
+impl ShortcutFile {
+    /// Same as [ShortcutFile::description] if the value is `Some`.
+    pub fn maybe_description(self, description: Option<impl Into<String>>) -> Self {
+        match description {
+            Some(description) => self.description(description),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::tooltip] if the value is `Some`.
+    pub fn maybe_tooltip(self, tooltip: Option<impl Into<String>>) -> Self {
+        match tooltip {
+            Some(tooltip) => self.tooltip(tooltip),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::generic_name] if the value is `Some`.
+    pub fn maybe_generic_name(self, generic_name: Option<impl Into<String>>) -> Self {
+        match generic_name {
+            Some(generic_name) => self.generic_name(generic_name),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::working_directory] if the value is `Some`.
+    pub fn maybe_working_directory(self, working_directory: Option<impl Into<PathBuf>>) -> Self {
+        match working_directory {
+            Some(working_directory) => self.working_directory(working_directory),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::icon] if the value is `Some`.
+    pub fn maybe_icon(self, icon: Option<impl Into<PathBuf>>) -> Self {
+        match icon {
+            Some(icon) => self.icon(icon),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::window_state] if the value is `Some`.
+    pub fn maybe_window_state(self, state: Option<WindowState>) -> Self {
+        match state {
+            Some(state) => self.window_state(state),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::hotkey] if the value is `Some`.
+    pub fn maybe_hotkey(self, combo: Option<Hotkey>) -> Self {
+        match combo {
+            Some(combo) => self.hotkey(combo),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::startup_wm_class] if the value is `Some`.
+    pub fn maybe_startup_wm_class(self, class: Option<impl Into<String>>) -> Self {
+        match class {
+            Some(class) => self.startup_wm_class(class),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::desktop_spec_version] if the value is `Some`.
+    pub fn maybe_desktop_spec_version(self, version: Option<impl Into<String>>) -> Self {
+        match version {
+            Some(version) => self.desktop_spec_version(version),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::startup_notify] if the value is `Some`.
+    pub fn maybe_startup_notify(self, startup_notify: Option<bool>) -> Self {
+        match startup_notify {
+            Some(startup_notify) => self.startup_notify(startup_notify),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::dbus_activatable] if the value is `Some`.
+    pub fn maybe_dbus_activatable(self, dbus_activatable: Option<bool>) -> Self {
+        match dbus_activatable {
+            Some(dbus_activatable) => self.dbus_activatable(dbus_activatable),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::single_main_window] if the value is `Some`.
+    pub fn maybe_single_main_window(self, single_main_window: Option<bool>) -> Self {
+        match single_main_window {
+            Some(single_main_window) => self.single_main_window(single_main_window),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::prefers_non_default_gpu] if the value is `Some`.
+    pub fn maybe_prefers_non_default_gpu(self, prefers_non_default_gpu: Option<bool>) -> Self {
+        match prefers_non_default_gpu {
+            Some(prefers_non_default_gpu) => self.prefers_non_default_gpu(prefers_non_default_gpu),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::try_exec] if the value is `Some`.
+    pub fn maybe_try_exec(self, path: Option<impl Into<PathBuf>>) -> Self {
+        match path {
+            Some(path) => self.try_exec(path),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::exec_prefix] if the value is `Some`.
+    pub fn maybe_exec_prefix(self, prefix: Option<impl Into<String>>) -> Self {
+        match prefix {
+            Some(prefix) => self.exec_prefix(prefix),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::entry_type] if the value is `Some`.
+    pub fn maybe_entry_type(self, entry_type: Option<EntryType>) -> Self {
+        match entry_type {
+            Some(entry_type) => self.entry_type(entry_type),
+            None => self,
+        }
+    }
+    /// Same as [ShortcutFile::app_id] if the value is `Some`.
+    pub fn maybe_app_id(self, app_id: Option<impl Into<String>>) -> Self {
+        match app_id {
+            Some(app_id) => self.app_id(app_id),
+            None => self,
+        }
+    }
+}
+/// Returns true if the icon is the name of an icon from the icon theme instead of a path.
+///
+/// A name has no directory and no image extension. Such as `firefox` or `org.mozilla.firefox`.
+/// Windows has no icon theme so this is always false.
+fn is_icon_name(icon: &Path) -> bool {
+    const IMAGE_EXTENSIONS: &[&str] = &["png", "svg", "svgz", "xpm", "ico"];
+    let is_image = icon
+        .extension()
+        .and_then(|v| v.to_str())
+        .is_some_and(|v| IMAGE_EXTENSIONS.iter().any(|e| v.eq_ignore_ascii_case(e)));
+    !cfg!(target_os = "windows")
+        && !is_image
+        && matches!(
+            icon.components().collect::<Vec<_>>().as_slice(),
+            [std::path::Component::Normal(_)]
+        )
+}
+/// Expands a leading `~` to the home directory and makes the path absolute.
+///
+/// The path is returned unchanged if the home directory or the current directory is unknown.
+fn expand_path(path: &Path) -> PathBuf {
+    let path = match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    };
+    std::path::absolute(&path).unwrap_or(path)
+}
+/// Replaces `\` with `/`. On Windows the path is also lowercased.
+fn normalize_path(path: &Path) -> PathBuf {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if cfg!(target_os = "windows") {
+        PathBuf::from(path.to_lowercase())
+    } else {
+        PathBuf::from(path)
+    }
+}
+/// The home directory of the current user.
+fn home_dir() -> Option<PathBuf> {
+    let key = if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    };
+    std::env::var_os(key)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+/// A path next to the file starting with `.tmp-` for writing it before it is renamed over or linked to the file.
+///
+/// Renaming is atomic on the same file system so a crash never leaves a partial file behind.
+fn temporary_path(path: &Path) -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    // Unique within the process so threads saving the same path do not collide.
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(
+        ".tmp-{}-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        file_name
+    ))
+}
+/// Splits a command line using POSIX shell quoting. See [ShortcutFile::args_from_command_line]
+fn split_command_line(value: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_argument = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    current.push(c);
+                }
+            }
+            '"' => {
+                in_argument = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => current.push(escaped),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => current.push('\\'),
+                        },
+                        c => current.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_argument = true;
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+/// Returns true if the path has the `.url` extension.
+fn is_url_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|v| v.eq_ignore_ascii_case(ShortcutFormat::Url.extension()))
+}
+/// Saves every shortcut to its path. See [ShortcutFile::save]
+///
+/// A failed shortcut does not stop the others from being saved.
+/// On Windows, COM is initialized once for the whole batch.
+pub fn save_all(
+    shortcuts: impl IntoIterator<Item = (ShortcutFile, PathBuf)>,
+) -> Vec<Result<PathBuf, FileShortcutError>> {
+    // Each save initializes COM itself if this fails.
+    #[cfg(target_os = "windows")]
+    let _com = windows::ComGuard::new().ok();
+    shortcuts
+        .into_iter()
+        .map(|(shortcut, to)| shortcut.save(to))
+        .collect()
+}
+/// A file found by [read_dir] and the result of reading it.
+pub type ReadDirEntry = (PathBuf, Result<ShortcutFile, FileShortcutError>);
+/// Reads every shortcut in a directory. See [ShortcutFile::read]
+///
+/// Files with the extension of the host's format and `.url` files are read. Other files are skipped.
+/// A file that fails to be read does not stop the others. Its error is returned with its path.
+/// Entries of the directory that can not be listed have no path so they are skipped and logged as a warning.
+///
+/// Fails only if the directory can not be read. Entries are sorted by path.
+pub fn read_dir(dir: impl AsRef<Path>) -> Result<Vec<ReadDirEntry>, FileShortcutError> {
+    let native = ShortcutFile::default().native_format().extension();
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(ErrorType::from)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(err) => {
+                log::warn!("Skipping an entry of the directory. {}", err);
+                continue;
+            }
+        };
+        let is_shortcut = path
+            .extension()
+            .is_some_and(|v| v.eq_ignore_ascii_case(native))
+            || is_url_file(&path);
+        if is_shortcut && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let shortcut = ShortcutFile::read(&path);
+            (path, shortcut)
+        })
+        .collect())
+}
+/// Replaces characters that are not allowed in a file name on any host with `_`
+///
+/// Leading and trailing whitespace and trailing dots are removed.
+fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    name.trim().trim_end_matches('.').to_string()
+}
+/// Reads the shortcut file. See [ShortcutFile::read]
+impl TryFrom<&Path> for ShortcutFile {
+    type Error = FileShortcutError;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        Self::read(path)
+    }
+}
+/// Reads the shortcut file. See [ShortcutFile::read]
+impl TryFrom<PathBuf> for ShortcutFile {
+    type Error = FileShortcutError;
+
+    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+        Self::read(path)
+    }
+}
+/// A one line summary. `<name> -> <target> <arguments>`
+///
+/// Arguments are omitted if there are none. Use `Debug` for every field.
+impl Display for ShortcutFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.name, self.target)?;
+        for argument in &self.arguments {
+            write!(f, " {}", argument)?;
+        }
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -184,13 +1960,767 @@ mod tests {
             super::ShortcutFile {
                 name: "My Shortcut".to_string(),
                 description: Some("This is a shortcut to my program.".to_string()),
-                path: "C:\\Program Files\\My Program.exe".into(),
+                target: super::ShortcutTarget::Path("C:\\Program Files\\My Program.exe".into()),
                 arguments: vec!["--my-argument".to_string()],
                 icon: None,
                 show_terminal: false,
                 categories: vec!["My Category".to_string()],
-                working_directory: None,
+                ..Default::default()
+            }
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn test_serde() {
+        use super::{Hotkey, HotkeyKey, ShortcutFile};
+        let shortcut = ShortcutFile::new("My Shortcut", "/usr/bin/my-program")
+            .description("This is a shortcut to my program.")
+            .arg("--my-argument")
+            .hotkey(Hotkey::new(HotkeyKey::Char('P')).ctrl().alt());
+        let json = serde_json::to_string(&shortcut).unwrap();
+        assert!(json.contains("\"Ctrl+Alt+P\""));
+        assert!(!json.contains("\"icon\""));
+        assert_eq!(
+            serde_json::from_str::<ShortcutFile>(&json).unwrap(),
+            shortcut
+        );
+
+        let minimal: ShortcutFile =
+            serde_json::from_str(r#"{"name": "My Shortcut", "path": "/usr/bin/my-program"}"#)
+                .unwrap();
+        assert_eq!(
+            minimal,
+            ShortcutFile::new("My Shortcut", "/usr/bin/my-program")
+        );
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_empty_name() {
+        let target = std::env::current_exe().unwrap();
+        let result = super::ShortcutFile::new("\n", target)
+            .save(std::env::temp_dir().join("test_empty_name.desktop"));
+        assert!(matches!(result, Err(super::FileShortcutError::EmptyName)));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_deprecated_field_code() {
+        let target = std::env::current_exe().unwrap();
+        let result = super::ShortcutFile::new("My Shortcut", target)
+            .arg("%d")
+            .save(std::env::temp_dir().join("test_deprecated_field_code.desktop"));
+        assert!(matches!(
+            result,
+            Err(super::FileShortcutError::DeprecatedFieldCode(code)) if code == "%d"
+        ));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_sanitize_name() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_sanitize_name.desktop");
+        super::ShortcutFile::new("My\u{7}Shortcut", target)
+            .save(&path)
+            .unwrap();
+        let shortcut = super::ShortcutFile::read(path).unwrap();
+        assert_eq!(shortcut.name, "MyShortcut");
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_clear() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_clear.desktop");
+        super::ShortcutFile::new("My Shortcut", &target)
+            .description("This is a shortcut to my program.")
+            .icon(&target)
+            .arg("--my-argument")
+            .save(&path)
+            .unwrap();
+        let shortcut = super::ShortcutFile::read(&path)
+            .unwrap()
+            .clear_icon()
+            .clear_description()
+            .clear_arguments();
+        assert_eq!(shortcut.icon, None);
+        assert_eq!(shortcut.description, None);
+        assert!(shortcut.arguments.is_empty());
+        shortcut.clone().save(&path).unwrap();
+        assert_eq!(super::ShortcutFile::read(&path).unwrap(), shortcut);
+    }
+    #[test]
+    pub fn test_apply_extension() {
+        use super::ShortcutFormat;
+        use std::path::PathBuf;
+        for format in [ShortcutFormat::NATIVE, ShortcutFormat::Url] {
+            let expected = PathBuf::from(format!("My Shortcut.{}", format.extension()));
+            // Missing
+            assert_eq!(
+                format.apply_extension(PathBuf::from("My Shortcut")),
+                expected
+            );
+            // Correct
+            assert_eq!(format.apply_extension(expected.clone()), expected);
+            // Wrong
+            assert_eq!(
+                format.apply_extension(PathBuf::from("My Shortcut.txt")),
+                PathBuf::from(format!("My Shortcut.txt.{}", format.extension()))
+            );
+        }
+        assert_eq!(
+            ShortcutFormat::Url.apply_extension(PathBuf::from("My Shortcut.URL")),
+            PathBuf::from("My Shortcut.URL")
+        );
+    }
+    #[test]
+    pub fn test_save_exact() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_save_exact.txt");
+        let written = super::ShortcutFile::new("My Shortcut", target)
+            .save_exact(&path)
+            .unwrap();
+        assert_eq!(written, path);
+        assert!(written.exists());
+    }
+    #[test]
+    pub fn test_save_returns_path() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_save_returns_path");
+        let written = super::ShortcutFile::new("My Shortcut", target)
+            .save(&path)
+            .unwrap();
+        assert_eq!(
+            written,
+            path.with_extension(super::ShortcutFormat::NATIVE.extension())
+        );
+        assert!(written.exists());
+    }
+    #[test]
+    pub fn test_sanitize_file_name() {
+        use super::sanitize_file_name;
+        assert_eq!(sanitize_file_name("My App"), "My App");
+        assert_eq!(sanitize_file_name("AC/DC"), "AC_DC");
+        assert_eq!(sanitize_file_name("C:\\Apps\\Run?"), "C__Apps_Run_");
+        assert_eq!(sanitize_file_name(" My App... "), "My App");
+        assert_eq!(sanitize_file_name("../"), ".._");
+    }
+    #[test]
+    pub fn test_save_to_dir() {
+        let target = std::env::current_exe().unwrap();
+        let written = super::ShortcutFile::new("Test/Save To Dir", target)
+            .save_to_dir(std::env::temp_dir())
+            .unwrap();
+        assert_eq!(
+            written,
+            std::env::temp_dir().join(format!(
+                "Test_Save To Dir.{}",
+                super::ShortcutFormat::NATIVE.extension()
+            ))
+        );
+        assert!(written.exists());
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_read_lenient() {
+        use super::{FileShortcutError, LinuxShortcutError, ParseOptions, ShortcutFile};
+        let path = std::env::temp_dir().join("test_read_lenient.desktop");
+        std::fs::write(
+            &path,
+            "[Desktop Entry]\nType=Application\nName=Test\nNot a key\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            ShortcutFile::read(&path),
+            Err(FileShortcutError::NativeError(LinuxShortcutError::ParseError { line: 4, message })) if message.contains("Not a key")
+        ));
+        let shortcut =
+            ShortcutFile::read_with_options(&path, ParseOptions::default().lenient()).unwrap();
+        assert_eq!(shortcut.name, "Test");
+        assert_eq!(shortcut.target, Default::default());
+    }
+    #[test]
+    pub fn test_save_unchecked() {
+        let written = super::ShortcutFile::new("My Shortcut", "/does/not/exist")
+            .save_unchecked(std::env::temp_dir().join("test_save_unchecked"))
+            .unwrap();
+        assert!(written.exists());
+    }
+    #[test]
+    pub fn test_expand_path() {
+        use super::{expand_path, home_dir};
+        use std::path::Path;
+        if let Some(home) = home_dir() {
+            assert_eq!(expand_path(Path::new("~/apps/foo")), home.join("apps/foo"));
+        }
+        assert_eq!(
+            expand_path(Path::new("apps/foo")),
+            std::env::current_dir().unwrap().join("apps/foo")
+        );
+        let absolute = std::env::current_exe().unwrap();
+        assert_eq!(expand_path(&absolute), absolute);
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_save_expand_paths() {
+        use super::{ShortcutFile, ShortcutTarget};
+        let path = ShortcutFile::new("My Shortcut", "Cargo.toml")
+            .expand_paths()
+            .save(std::env::temp_dir().join("test_save_expand_paths.desktop"))
+            .unwrap();
+        assert_eq!(
+            ShortcutFile::read(path).unwrap().target,
+            ShortcutTarget::Path(std::env::current_dir().unwrap().join("Cargo.toml"))
+        );
+    }
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    pub fn test_is_icon_name() {
+        use super::is_icon_name;
+        use std::path::Path;
+        assert!(is_icon_name(Path::new("firefox")));
+        assert!(is_icon_name(Path::new("org.mozilla.firefox")));
+        assert!(!is_icon_name(Path::new("firefox.png")));
+        assert!(!is_icon_name(Path::new("icons/firefox")));
+        assert!(!is_icon_name(Path::new("/usr/share/icons/firefox")));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_icon_name() {
+        let target = std::env::current_exe().unwrap();
+        let path = super::ShortcutFile::new("My Shortcut", target)
+            .icon("firefox")
+            .save(std::env::temp_dir().join("test_icon_name.desktop"))
+            .unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains("Icon=firefox\n"));
+    }
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    #[test]
+    pub fn test_render() {
+        let rendered = super::ShortcutFile::new("My Shortcut", "/usr/bin/my-program")
+            .arg("--my-argument")
+            .render()
+            .unwrap();
+        assert!(rendered.starts_with("[Desktop Entry]\n"));
+        assert!(rendered.contains("Exec=/usr/bin/my-program --my-argument\n"));
+    }
+    #[test]
+    pub fn test_save_all() {
+        use super::{save_all, FileShortcutError, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let dir = std::env::temp_dir();
+        let results = save_all([
+            (
+                ShortcutFile::new("First", &target),
+                dir.join("test_save_all_first"),
+            ),
+            (
+                ShortcutFile::new("Missing", "/does/not/exist"),
+                dir.join("test_save_all_missing"),
+            ),
+            (
+                ShortcutFile::new("Third", &target),
+                dir.join("test_save_all_third"),
+            ),
+        ]);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].as_ref().is_ok_and(|v| v.exists()));
+        assert!(matches!(
+            results[1],
+            Err(FileShortcutError::TargetPathDoesNotExist(_))
+        ));
+        assert!(results[2].as_ref().is_ok_and(|v| v.exists()));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_save_directory() {
+        use super::{EntryType, ShortcutFile};
+        let mut shortcut = ShortcutFile::default().entry_type(EntryType::Directory);
+        shortcut.name = "Test Save Directory".to_string();
+        let path = shortcut.clone().save_to_dir(std::env::temp_dir()).unwrap();
+        assert_eq!(path.extension().unwrap(), "directory");
+        assert_eq!(ShortcutFile::read(path).unwrap(), shortcut);
+    }
+    #[test]
+    pub fn test_show_terminal_value() {
+        let shortcut = super::ShortcutFile::new("My Shortcut", "/usr/bin/my-program");
+        assert!(shortcut.clone().show_terminal_value(true).show_terminal);
+        assert!(
+            !shortcut
+                .show_terminal()
+                .show_terminal_value(false)
+                .show_terminal
+        );
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_overwrite() {
+        use super::ShortcutFile;
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_overwrite.desktop");
+        ShortcutFile::new("My Shortcut", &target)
+            .description("A long description that is longer than the other shortcut.")
+            .keywords(vec!["one".to_string(), "two".to_string()])
+            .save(&path)
+            .unwrap();
+        let shortcut = ShortcutFile::new("Short", &target);
+        shortcut.clone().save(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            shortcut.render().unwrap()
+        );
+    }
+    #[test]
+    pub fn test_save_if_absent() {
+        use super::{FileShortcutError, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_save_if_absent");
+        let written = ShortcutFile::new("My Shortcut", &target)
+            .save(&path)
+            .unwrap();
+        let result = ShortcutFile::new("My Shortcut", &target).save_if_absent(&path);
+        assert!(matches!(result, Err(FileShortcutError::AlreadyExists(v)) if v == written));
+        std::fs::remove_file(&written).unwrap();
+        assert_eq!(
+            ShortcutFile::new("My Shortcut", &target)
+                .save_if_absent(&path)
+                .unwrap(),
+            written
+        );
+        // The temporary file is removed after it is linked to the path.
+        let file_name = written.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(!std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(Result::ok)
+            .any(|v| {
+                let name = v.file_name().to_string_lossy().into_owned();
+                name.starts_with(".tmp-") && name.ends_with(&file_name)
+            }));
+    }
+    #[test]
+    pub fn test_validate() {
+        use super::{FileShortcutError, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let errors = ShortcutFile::new("My Shortcut", target)
+            .icon("/does/not/exist.png")
+            .working_directory("/does/not/exist")
+            .validate()
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            FileShortcutError::IconPathDoesNotExist(_)
+        ));
+        assert!(matches!(
+            errors[1],
+            FileShortcutError::WorkingDirectoryPathDoesNotExist(_)
+        ));
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_retarget() {
+        use super::{ShortcutFile, ShortcutTarget};
+        let target = std::env::current_exe().unwrap();
+        let new_target = target.parent().unwrap().to_path_buf();
+        let path = ShortcutFile::new("My Shortcut", &target)
+            .save(std::env::temp_dir().join("test_retarget.desktop"))
+            .unwrap();
+        ShortcutFile::read(&path)
+            .unwrap()
+            .target(&new_target)
+            .save(&path)
+            .unwrap();
+        assert_eq!(
+            ShortcutFile::read(&path).unwrap().target,
+            ShortcutTarget::Path(new_target)
+        );
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_try_from() {
+        use super::ShortcutFile;
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("My Shortcut", target);
+        let path = shortcut
+            .clone()
+            .save(std::env::temp_dir().join("test_try_from.desktop"))
+            .unwrap();
+        assert_eq!(ShortcutFile::try_from(path.as_path()).unwrap(), shortcut);
+        assert_eq!(ShortcutFile::try_from(path).unwrap(), shortcut);
+    }
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    pub async fn test_async() {
+        use super::ShortcutFile;
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("My Shortcut", target).arg("--my-argument");
+        let path = shortcut
+            .clone()
+            .save_async(std::env::temp_dir().join("test_async"))
+            .await
+            .unwrap();
+        assert_eq!(ShortcutFile::read_async(path).await.unwrap(), shortcut);
+    }
+    #[test]
+    pub fn test_read_url() {
+        let path = std::env::temp_dir().join("test_read_url.url");
+        let shortcut = super::ShortcutFile::new_url("test_read_url", "https://example.com");
+        shortcut
+            .clone()
+            .save_as(&path, super::ShortcutFormat::Url)
+            .unwrap();
+        assert_eq!(super::ShortcutFile::read(&path).unwrap(), shortcut);
+    }
+    #[cfg(target_os = "windows")]
+    #[test]
+    pub fn test_save_url() {
+        let path = std::env::temp_dir().join("test_save_url.url");
+        super::ShortcutFile::new_url("My Shortcut", "https://example.com")
+            .save(&path)
+            .unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.starts_with("[InternetShortcut]"));
+        assert!(content.contains("URL=https://example.com"));
+    }
+    #[test]
+    pub fn test_env_not_supported() {
+        let target = std::env::current_exe().unwrap();
+        let result = super::ShortcutFile::new("My Shortcut", target)
+            .env("KEY", "VALUE")
+            .save_as(
+                std::env::temp_dir().join("test_env_not_supported.url"),
+                super::ShortcutFormat::Url,
+            );
+        assert!(matches!(
+            result,
+            Err(super::FileShortcutError::EnvNotSupported(
+                super::ShortcutFormat::Url
+            ))
+        ));
+    }
+    #[test]
+    pub fn test_read_dir() {
+        use super::{read_dir, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let dir = std::env::temp_dir().join("test_read_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = ShortcutFile::new("First", &target)
+            .save_to_dir(&dir)
+            .unwrap();
+        ShortcutFile::new("Second", &target)
+            .save_to_dir(&dir)
+            .unwrap();
+        let broken = first
+            .with_file_name("Broken")
+            .with_extension(first.extension().unwrap());
+        std::fs::write(&broken, "Not a shortcut").unwrap();
+        std::fs::write(dir.join("notes.txt"), "Not a shortcut").unwrap();
+
+        let shortcuts = read_dir(&dir).unwrap();
+        assert_eq!(shortcuts.len(), 3);
+        assert_eq!(shortcuts[0].0, broken);
+        assert!(shortcuts[0].1.is_err());
+        assert_eq!(shortcuts[1].0, first);
+        assert_eq!(shortcuts[1].1.as_ref().unwrap().name, "First");
+        assert_eq!(shortcuts[2].1.as_ref().unwrap().name, "Second");
+    }
+    #[test]
+    pub fn test_semantic_eq() {
+        use super::ShortcutFile;
+        let unix = ShortcutFile::new("Test", "/a/b").working_directory("/a");
+        let windows = ShortcutFile::new("Test", "\\a\\b").working_directory("\\a");
+        assert_ne!(unix, windows);
+        assert!(unix.semantic_eq(&windows));
+        assert!(!unix.semantic_eq(&ShortcutFile::new("Test", "/a/c").working_directory("/a")));
+        assert!(!unix.semantic_eq(&windows.clone().description("Different")));
+        #[cfg(target_os = "windows")]
+        assert!(unix.semantic_eq(&ShortcutFile::new("Test", "/A/B").working_directory("/a")));
+    }
+    #[test]
+    pub fn test_display() {
+        use super::ShortcutFile;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("-l")
+            .arg("/tmp")
+            .description("Not shown");
+        assert_eq!(shortcut.to_string(), "Test -> /usr/bin/ls -l /tmp");
+        let url = ShortcutFile::new_url("Example", "https://example.com");
+        assert_eq!(url.to_string(), "Example -> https://example.com");
+    }
+    #[test]
+    pub fn test_extend_arguments() {
+        use super::ShortcutFile;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("-l")
+            .extend_arguments(["-a", "-h"])
+            .arg("/tmp")
+            .category("Utility")
+            .extend_categories(vec![String::from("System")]);
+        assert_eq!(shortcut.arguments, vec!["-l", "-a", "-h", "/tmp"]);
+        assert_eq!(shortcut.categories, vec!["Utility", "System"]);
+    }
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    #[test]
+    pub fn test_write_to() {
+        use super::ShortcutFile;
+        let mut buffer = Vec::new();
+        ShortcutFile::new("Test", "/usr/bin/ls")
+            .arg("-l")
+            .write_to(&mut buffer)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "[Desktop Entry]\nVersion=1.5\nType=Application\nName=Test\nExec=/usr/bin/ls -l\nTerminal=false\n"
+        );
+    }
+    #[cfg(any(target_os = "linux", feature = "desktop"))]
+    #[test]
+    pub fn test_read_from() {
+        use super::ShortcutFile;
+        let content: &[u8] = b"[Desktop Entry]\nType=Application\nName=Test\nExec=/usr/bin/ls -l\n";
+        let shortcut = ShortcutFile::read_from(content).unwrap();
+        assert_eq!(shortcut, ShortcutFile::new("Test", "/usr/bin/ls").arg("-l"));
+    }
+    #[test]
+    pub fn test_invalid_combination() {
+        use super::{EntryType, FileShortcutError, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let invalid = [
+            ShortcutFile::new_url("Test", "https://example.com").arg("--flag"),
+            ShortcutFile::new("Test", &target)
+                .entry_type(EntryType::Directory)
+                .arg("--flag"),
+            ShortcutFile::new("Test", &target).entry_type(EntryType::Link),
+            ShortcutFile::new_url("Test", "https://example.com").entry_type(EntryType::Application),
+        ];
+        for shortcut in invalid {
+            let errors = shortcut.validate().unwrap_err();
+            assert!(matches!(
+                errors[0],
+                FileShortcutError::InvalidCombination(_)
+            ));
+            let result =
+                shortcut.save_unchecked(std::env::temp_dir().join("test_invalid_combination"));
+            assert!(matches!(
+                result,
+                Err(FileShortcutError::InvalidCombination(_))
+            ));
+        }
+        assert!(ShortcutFile::new("Test", &target)
+            .arg("--flag")
+            .validate()
+            .is_ok());
+    }
+    #[test]
+    pub fn test_try_exec_not_validated() {
+        use super::ShortcutFile;
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("Test", target).try_exec("/does/not/exist");
+        assert!(shortcut.validate().is_ok());
+    }
+    #[test]
+    pub fn test_broken_reasons() {
+        use super::{FileShortcutError, ShortcutFile};
+        let target = std::env::current_exe().unwrap();
+        let valid = ShortcutFile::new("Test", &target).working_directory(target.parent().unwrap());
+        assert!(valid.is_target_valid());
+        assert!(valid.broken_reasons().is_empty());
+
+        let broken = ShortcutFile::new("Test", "/does/not/exist").working_directory("/does/not");
+        assert!(!broken.is_target_valid());
+        let reasons = broken.broken_reasons();
+        assert_eq!(reasons.len(), 2);
+        assert!(matches!(
+            reasons[0],
+            FileShortcutError::TargetPathDoesNotExist(_)
+        ));
+        assert!(matches!(
+            reasons[1],
+            FileShortcutError::WorkingDirectoryPathDoesNotExist(_)
+        ));
+
+        let broken_icon = ShortcutFile::new("Test", &target).icon("/does/not/exist.png");
+        assert!(broken_icon.is_target_valid());
+        assert_eq!(broken_icon.broken_reasons().len(), 1);
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_setters() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_setters.desktop");
+        super::ShortcutFile::new("My Shortcut", &target)
+            .save(&path)
+            .unwrap();
+        let mut shortcut = super::ShortcutFile::read(&path).unwrap();
+        shortcut
+            .set_description("Edited")
+            .set_icon(&target)
+            .set_arguments(["--one", "--two"])
+            .set_show_terminal(true);
+        assert_eq!(shortcut.description.as_deref(), Some("Edited"));
+        assert_eq!(shortcut.icon.as_deref(), Some(target.as_path()));
+        assert_eq!(shortcut.arguments, vec!["--one", "--two"]);
+        shortcut.clone().save(&path).unwrap();
+        assert_eq!(super::ShortcutFile::read(&path).unwrap(), shortcut);
+    }
+    #[cfg(target_os = "windows")]
+    #[test]
+    pub fn test_strict_platform() {
+        use super::{FileShortcutError, ShortcutFormat, WriteOptions};
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_strict_platform.lnk");
+        let shortcut = super::ShortcutFile::new("Test", &target).category("Utility");
+        let err = shortcut
+            .clone()
+            .save_with_options(&path, WriteOptions::default().strict_platform(true))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FileShortcutError::UnsupportedOnPlatform {
+                field: "categories",
+                format: ShortcutFormat::Lnk,
+            }
+        ));
+        shortcut.save(&path).unwrap();
+    }
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_strict_platform() {
+        use super::{FileShortcutError, ShortcutFormat, WriteOptions};
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("test_strict_platform.desktop");
+        let shortcut = super::ShortcutFile::new("Test", &target).run_as_admin();
+        let err = shortcut
+            .clone()
+            .save_with_options(&path, WriteOptions::default().strict_platform(true))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FileShortcutError::UnsupportedOnPlatform {
+                field: "run_as_admin",
+                format: ShortcutFormat::Desktop,
             }
+        ));
+        shortcut.save(&path).unwrap();
+    }
+    #[test]
+    pub fn test_fingerprint() {
+        use super::ShortcutFile;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").arg("-l");
+        assert_eq!(shortcut.fingerprint(), shortcut.clone().fingerprint());
+        assert_eq!(
+            shortcut.fingerprint(),
+            ShortcutFile::new("Test", "/usr/bin/ls")
+                .arg("-l")
+                .fingerprint()
+        );
+        assert_ne!(
+            shortcut.fingerprint(),
+            shortcut.clone().arg("-a").fingerprint()
+        );
+        assert_ne!(
+            shortcut.fingerprint(),
+            shortcut.clone().description("Changed").fingerprint()
+        );
+    }
+    #[test]
+    pub fn test_icon_from_target() {
+        use super::ShortcutFile;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .icon_index(3)
+            .icon_from_target();
+        assert_eq!(
+            shortcut.icon.as_deref(),
+            Some(std::path::Path::new("/usr/bin/ls"))
+        );
+        assert_eq!(shortcut.icon_index, 0);
+        let shortcut = ShortcutFile::new_url("Test", "https://example.com").icon_from_target();
+        assert_eq!(shortcut.icon, None);
+    }
+    #[test]
+    pub fn test_category_enum() {
+        use super::{Category, ShortcutFile};
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .category_enum(Category::Development)
+            .category("X-MyCategory");
+        assert_eq!(shortcut.categories, vec!["Development", "X-MyCategory"]);
+    }
+    #[test]
+    pub fn test_args_from_command_line() {
+        use super::{split_command_line, ShortcutFile};
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/app")
+            .arg("-v")
+            .args_from_command_line("--name \"John Doe\" --flag");
+        assert_eq!(
+            shortcut.arguments,
+            vec!["-v", "--name", "John Doe", "--flag"]
+        );
+        assert_eq!(
+            split_command_line(r#"'it''s' "say \"hi\"" a\ b "" c\d"#),
+            vec!["its", "say \"hi\"", "a b", "", "cd"]
+        );
+        assert_eq!(split_command_line(r#""\n\$""#), vec!["\\n$"]);
+        assert!(split_command_line("   ").is_empty());
+    }
+    #[test]
+    pub fn test_working_directory_from_target() {
+        use super::ShortcutFile;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").working_directory_from_target();
+        assert_eq!(
+            shortcut.working_directory.as_deref(),
+            Some(std::path::Path::new("/usr/bin"))
+        );
+        for target in ["ls", "/", ""] {
+            let shortcut = ShortcutFile::new("Test", target).working_directory_from_target();
+            assert_eq!(shortcut.working_directory, None);
+        }
+    }
+    /// The `.desktop` functions can be used without the host dispatch of [super::ShortcutFile::save]
+    #[cfg(all(feature = "desktop", not(target_os = "windows")))]
+    #[test]
+    pub fn test_desktop_feature() {
+        use super::linux::{read_desktop_entry, write_desktop_entry, LinuxShortcutError};
+        use super::{ParseOptions, ShortcutFile};
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").arg("-l");
+        let mut buffer = Vec::new();
+        write_desktop_entry(shortcut.clone(), &mut buffer).unwrap();
+        let read: Result<ShortcutFile, LinuxShortcutError> =
+            read_desktop_entry(buffer.as_slice(), ParseOptions::default());
+        assert_eq!(read.unwrap(), shortcut);
+    }
+    #[test]
+    pub fn test_read_missing_target() {
+        use super::{FileShortcutError, ShortcutFile};
+        let path = super::ShortcutFile::new("Test", "/does/not/exist")
+            .icon("/does/not/exist.png")
+            .save_unchecked(std::env::temp_dir().join("test_read_missing_target"))
+            .unwrap();
+        let shortcut = ShortcutFile::read(&path).unwrap();
+        assert!(!shortcut.is_target_valid());
+        let moved = std::env::temp_dir().join("test_read_missing_target_moved");
+        assert!(matches!(
+            shortcut.clone().save(&moved),
+            Err(FileShortcutError::TargetPathDoesNotExist(_))
+        ));
+        assert!(shortcut.save_unchecked(&moved).unwrap().exists());
+    }
+    #[test]
+    pub fn test_maybe() {
+        use super::{ShortcutFile, WindowState};
+        let description: Option<&str> = None;
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls")
+            .description("Kept")
+            .maybe_description(description)
+            .maybe_icon(Some("/usr/share/icons/ls.png"))
+            .maybe_working_directory(None::<&str>)
+            .maybe_window_state(Some(WindowState::Maximized))
+            .maybe_startup_notify(None);
+        assert_eq!(shortcut.description.as_deref(), Some("Kept"));
+        assert_eq!(
+            shortcut.icon.as_deref(),
+            Some(std::path::Path::new("/usr/share/icons/ls.png"))
         );
+        assert_eq!(shortcut.working_directory, None);
+        assert_eq!(shortcut.window_state, Some(WindowState::Maximized));
+        assert_eq!(shortcut.startup_notify, None);
     }
 }
@@ -0,0 +1,57 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// What a shortcut points to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum ShortcutTarget {
+    /// Path to an executable or file.
+    Path(PathBuf),
+    /// A URL such as `https://example.com`
+    ///
+    /// On Windows, this is saved as a `.url` file.
+    /// On Linux, this is saved as a `Type=Link` desktop entry.
+    Url(String),
+    /// A location in the Windows Shell namespace that has no path.
+    /// Such as `shell:RecycleBinFolder` or `::{645FF040-5081-101B-9F08-00AA002F954E}`
+    ///
+    /// Only supported by `.lnk` files on Windows. The location is not checked to exist.
+    Shell(String),
+}
+impl Default for ShortcutTarget {
+    fn default() -> Self {
+        Self::Path(PathBuf::new())
+    }
+}
+impl ShortcutTarget {
+    /// Returns the path if the target is a [ShortcutTarget::Path]
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            Self::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+    /// Returns the URL if the target is a [ShortcutTarget::Url]
+    pub fn as_url(&self) -> Option<&str> {
+        match self {
+            Self::Url(url) => Some(url),
+            _ => None,
+        }
+    }
+}
+impl Display for ShortcutTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{}", url),
+            Self::Shell(location) => write!(f, "{}", location),
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use super::{ParseOptions, ShortcutFile, ShortcutTarget};
+use std::{
+    borrow::Cow,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use thiserror::Error;
+#[derive(Debug, Error)]
+pub enum UrlShortcutError {
+    #[error(transparent)]
+    IOErr(#[from] std::io::Error),
+    #[error("Path was not valid UTF-8")]
+    PathNotValidUTF8,
+    #[error("Missing Value: {0}")]
+    MissingValue(&'static str),
+    #[error("Shortcut target {0} is not supported by .url files")]
+    UnsupportedTarget(ShortcutTarget),
+}
+/// Saves a Shortcut as an Internet Shortcut (`.url`) file.
+///
+/// A target path is written as a `file://` URL.
+pub fn save_shortcut_file(
+    shortcut: ShortcutFile,
+    to: impl AsRef<Path>,
+) -> Result<(), UrlShortcutError> {
+    debug!(
+        "Creating Internet Shortcut to {} at {:?}",
+        shortcut.target,
+        to.as_ref()
+    );
+    let url = match &shortcut.target {
+        ShortcutTarget::Path(path) => path_to_file_url(path)?,
+        ShortcutTarget::Url(url) => url.clone(),
+        target => return Err(UrlShortcutError::UnsupportedTarget(target.clone())),
+    };
+    let icon = shortcut
+        .icon
+        .map(|v| {
+            v.to_str()
+                .map(|v| v.to_owned())
+                .ok_or(UrlShortcutError::PathNotValidUTF8)
+        })
+        .transpose()?;
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(to)?;
+    let mut writer = std::io::BufWriter::new(file);
+    writeln!(writer, "[InternetShortcut]")?;
+    writeln!(writer, "URL={}", encode_url(&url))?;
+    if let Some(icon) = icon {
+        writeln!(writer, "IconFile={}", icon)?;
+        writeln!(writer, "IconIndex={}", shortcut.icon_index)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+pub fn read_shortcut_file(path: impl AsRef<Path>) -> Result<ShortcutFile, UrlShortcutError> {
+    read_shortcut_file_with_options(path, ParseOptions::default())
+}
+/// Reads an Internet Shortcut (`.url`) file.
+///
+/// A `file://` URL is read as a target path.
+/// The name of the shortcut is taken from the file stem as `.url` files do not store one.
+pub fn read_shortcut_file_with_options(
+    path: impl AsRef<Path>,
+    options: ParseOptions,
+) -> Result<ShortcutFile, UrlShortcutError> {
+    let name = path
+        .as_ref()
+        .file_stem()
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let read = std::fs::read_to_string(path)?;
+    let mut url = None;
+    let mut icon = None;
+    let mut icon_index = 0;
+    // Keys of other groups such as `[DEFAULT]` are skipped.
+    let mut in_internet_shortcut = false;
+    for line in read.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_internet_shortcut = line.eq_ignore_ascii_case("[InternetShortcut]");
+            continue;
+        }
+        if !in_internet_shortcut {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "URL" => url = Some(decode_url(value).into_owned()),
+            "IconFile" => icon = Some(PathBuf::from(value)),
+            "IconIndex" => icon_index = value.parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    let url = match url {
+        Some(url) => url,
+        None if options.lenient => String::new(),
+        None => return Err(UrlShortcutError::MissingValue("URL")),
+    };
+    let target = match file_url_to_path(&url) {
+        Some(path) => ShortcutTarget::Path(path),
+        None => ShortcutTarget::Url(url),
+    };
+    Ok(ShortcutFile {
+        name,
+        target,
+        icon,
+        icon_index,
+        ..Default::default()
+    })
+}
+/// Converts an absolute path into a `file://` URL.
+fn path_to_file_url(path: &Path) -> Result<String, UrlShortcutError> {
+    let path = path
+        .to_str()
+        .ok_or(UrlShortcutError::PathNotValidUTF8)?
+        .replace('\\', "/");
+    if path.starts_with('/') {
+        Ok(format!("file://{}", path))
+    } else {
+        Ok(format!("file:///{}", path))
+    }
+}
+/// Returns true if the byte is not allowed in a URL and has to be percent-encoded.
+fn needs_encoding(byte: u8) -> bool {
+    !byte.is_ascii_graphic()
+        || matches!(
+            byte,
+            b'"' | b'<' | b'>' | b'\\' | b'^' | b'`' | b'{' | b'|' | b'}'
+        )
+}
+/// Percent-encodes spaces, non-ASCII and other characters that are not allowed in a URL.
+///
+/// A `%` is kept as is so URLs that are already encoded are not encoded twice.
+pub(super) fn encode_url(url: &str) -> Cow<'_, str> {
+    if !url.bytes().any(needs_encoding) {
+        return Cow::Borrowed(url);
+    }
+    let mut encoded = String::with_capacity(url.len() + 8);
+    for byte in url.bytes() {
+        if needs_encoding(byte) {
+            encoded.push_str(&format!("%{:02X}", byte));
+        } else {
+            encoded.push(byte as char);
+        }
+    }
+    Cow::Owned(encoded)
+}
+/// Reverses [encode_url].
+///
+/// Only characters that [encode_url] encodes are decoded. Such as `%20`.
+/// Others such as `%2F` are kept as they change the meaning of the URL.
+pub(super) fn decode_url(url: &str) -> Cow<'_, str> {
+    if !url.contains('%') {
+        return Cow::Borrowed(url);
+    }
+    let bytes = url.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = url
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .filter(|v| needs_encoding(*v));
+        match byte {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        // Not encoded by [encode_url] so it is kept as is.
+        Err(_) => Cow::Borrowed(url),
+    }
+}
+/// Reverses [path_to_file_url]. Returns `None` if the URL is not a `file://` URL.
+fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?;
+    // `file:///C:/Windows` is a Windows path.
+    let path = match path.strip_prefix('/') {
+        Some(windows) if windows.get(1..2) == Some(":") => windows,
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_url, encode_url, file_url_to_path, path_to_file_url, read_shortcut_file,
+        save_shortcut_file,
+    };
+    use crate::shortcut_files::ShortcutFile;
+    use crate::shortcut_files::ShortcutTarget;
+    use std::path::Path;
+
+    #[test]
+    fn test_path_to_file_url() {
+        assert_eq!(
+            path_to_file_url(Path::new("/usr/bin/ls")).unwrap(),
+            "file:///usr/bin/ls"
+        );
+        assert_eq!(
+            path_to_file_url(Path::new("C:\\Windows\\notepad.exe")).unwrap(),
+            "file:///C:/Windows/notepad.exe"
+        );
+    }
+    #[test]
+    fn test_file_url_to_path() {
+        assert_eq!(
+            file_url_to_path("file:///usr/bin/ls"),
+            Some("/usr/bin/ls".into())
+        );
+        assert_eq!(
+            file_url_to_path("file:///C:/Windows/notepad.exe"),
+            Some("C:/Windows/notepad.exe".into())
+        );
+        assert_eq!(file_url_to_path("https://example.com"), None);
+    }
+    #[test]
+    fn test_save_shortcut_file() {
+        let shortcut = ShortcutFile::new("Test", "/usr/bin/ls").icon("/usr/share/icons/ls.png");
+        let path = std::env::temp_dir().join("test.url");
+        save_shortcut_file(shortcut, &path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "[InternetShortcut]\nURL=file:///usr/bin/ls\nIconFile=/usr/share/icons/ls.png\nIconIndex=0\n"
+        );
+    }
+    #[test]
+    fn test_save_url_target() {
+        let shortcut = ShortcutFile::new_url("Test", "https://example.com");
+        let path = std::env::temp_dir().join("test_url_target.url");
+        save_shortcut_file(shortcut, &path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "[InternetShortcut]\nURL=https://example.com\n"
+        );
+    }
+    #[test]
+    fn test_read_shortcut_file() {
+        let shortcut = ShortcutFile::new_url("test_read_url_file", "https://example.com")
+            .icon("/usr/share/icons/example.png")
+            .icon_index(2);
+        let path = std::env::temp_dir().join("test_read_url_file.url");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+
+        let shortcut = ShortcutFile::new("test_read_url_file", "/usr/bin/ls");
+        save_shortcut_file(shortcut, &path).unwrap();
+        assert_eq!(
+            read_shortcut_file(&path).unwrap().target,
+            ShortcutTarget::Path("/usr/bin/ls".into())
+        );
+    }
+    #[test]
+    fn test_encode_url() {
+        assert_eq!(encode_url("https://example.com"), "https://example.com");
+        assert_eq!(encode_url("https://x.test/a b"), "https://x.test/a%20b");
+        assert_eq!(
+            encode_url("https://x.test/?q=café"),
+            "https://x.test/?q=caf%C3%A9"
+        );
+        assert_eq!(encode_url("https://x.test/a%20b"), "https://x.test/a%20b");
+
+        assert_eq!(decode_url("https://x.test/a%20b"), "https://x.test/a b");
+        assert_eq!(
+            decode_url("https://x.test/?q=caf%C3%A9"),
+            "https://x.test/?q=café"
+        );
+        assert_eq!(decode_url("https://x.test/a%2Fb"), "https://x.test/a%2Fb");
+        assert_eq!(decode_url("https://x.test/100%"), "https://x.test/100%");
+    }
+    #[test]
+    fn test_encoded_url_round_trip() {
+        for url in ["https://x.test/a b", "https://x.test/?q=café"] {
+            let shortcut = ShortcutFile::new_url("test_encoded_url", url);
+            let path = std::env::temp_dir().join("test_encoded_url.url");
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(!content.contains(' ') && content.is_ascii());
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+        }
+    }
+}
@@ -1,46 +1,100 @@
-use super::ShortcutFile;
+use super::{
+    temporary_path, Hotkey, HotkeyKey, HotkeyModifiers, ParseOptions, ShortcutFile, ShortcutTarget,
+    WindowState,
+};
 use std::{
-    ffi::{CString, NulError, OsString},
+    borrow::Cow,
+    ffi::OsString,
+    fs::File,
+    io::{ErrorKind, Read},
     iter::once,
-    os::windows::ffi::OsStrExt,
+    os::windows::ffi::{OsStrExt, OsStringExt},
     path::{Path, PathBuf},
-    sync::Once,
+    string::FromUtf16Error,
 };
 
 use log::debug;
 use thiserror::Error;
 use windows::{
-    core::{ComInterface, PCSTR, PCWSTR},
+    core::{ComInterface, GUID, PCWSTR},
     Win32::{
-        Foundation::TRUE,
+        Foundation::{LocalFree, HANDLE, HLOCAL, MAX_PATH, RPC_E_CHANGED_MODE, TRUE},
         System::Com::{
-            CoCreateInstance, CoInitializeEx, IPersistFile, CLSCTX_INPROC_SERVER,
-            COINIT_MULTITHREADED,
+            CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, IBindCtx,
+            IPersistFile, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED, STGM_READ,
         },
         UI::{
+            Controls::{HOTKEYF_ALT, HOTKEYF_CONTROL, HOTKEYF_SHIFT, INFOTIPSIZE},
+            Input::KeyboardAndMouse::{VK_F1, VK_F24},
             Shell::*,
-            WindowsAndMessaging::{SW_HIDE, SW_SHOW},
+            WindowsAndMessaging::{
+                SHOW_WINDOW_CMD, SW_HIDE, SW_SHOW, SW_SHOWMAXIMIZED, SW_SHOWMINIMIZED,
+                SW_SHOWMINNOACTIVE, SW_SHOWNORMAL,
+            },
         },
     },
 };
 
-static CO_INITIALIZE_ONCE: Once = Once::new();
-
-fn initialize_com() {
-    CO_INITIALIZE_ONCE.call_once(|| unsafe {
-        CoInitializeEx(None, COINIT_MULTITHREADED).ok();
-    })
+/// Initializes COM on the current thread until dropped.
+///
+/// If the thread already uses a different apartment, such as a single threaded apartment
+/// initialized by the application, that apartment is used and left untouched.
+/// Otherwise COM is uninitialized again on drop so the apartment of the thread is not changed.
+pub(crate) struct ComGuard {
+    uninitialize: bool,
+}
+impl ComGuard {
+    pub(crate) fn new() -> Result<Self, WindowsShortcutError> {
+        match unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) } {
+            Ok(()) => Ok(Self { uninitialize: true }),
+            Err(err) if err.code() == RPC_E_CHANGED_MODE => Ok(Self {
+                uninitialize: false,
+            }),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.uninitialize {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+/// Resolves a known folder of the current user. Such as [FOLDERID_Programs]
+pub(crate) fn known_folder(id: &GUID) -> Result<PathBuf, WindowsShortcutError> {
+    let path = unsafe { SHGetKnownFolderPath(id, KF_FLAG_DEFAULT, HANDLE::default())? };
+    let folder = OsString::from_wide(unsafe { path.as_wide() });
+    unsafe { CoTaskMemFree(Some(path.0 as *const _)) };
+    Ok(PathBuf::from(folder))
 }
 
 #[derive(Debug, Error)]
 pub enum WindowsShortcutError {
-    #[error("Path was unable to be converted into a CString. {0:?}")]
-    PathToStringError(OsString),
-    #[error("String was unable to be converted into a CString. {0}")]
-    StringToCStringError(#[from] NulError),
+    #[error("String returned by Windows was not valid UTF-16. {0}")]
+    StringFromUtf16Error(#[from] FromUtf16Error),
+    #[error("Shortcut target {0} is not supported by .lnk files. Use a .url file instead.")]
+    UnsupportedTarget(ShortcutTarget),
+    #[error("Hotkey {0} can not be used in a Windows shortcut.")]
+    UnsupportedHotkey(Hotkey),
+    #[error("File is not a Shell Link")]
+    NotAShellLink,
+    #[error(transparent)]
+    IOErr(#[from] std::io::Error),
     #[error("Internal Windows Error. {0}")]
     WindowsError(#[from] ::windows::core::Error),
+    /// A call to the Shell Link failed. Contains the name of the method that was called.
+    #[error("{operation} failed. {source}")]
+    SetOperationFailed {
+        operation: &'static str,
+        source: ::windows::core::Error,
+    },
 }
+/// The header size followed by the CLSID `00021401-0000-0000-C000-000000000046` that every Shell Link starts with.
+const SHELL_LINK_MAGIC: [u8; 20] = [
+    0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x46,
+];
 /// Saves a Shortcut to a File. Uses the Win32 API.
 ///
 /// I would rather not use the Win32 API.
@@ -50,69 +104,635 @@ pub fn save_shortcut_file(
     to: impl Into<PathBuf>,
 ) -> Result<(), WindowsShortcutError> {
     let to = to.into();
-    debug!("Creating Shortcut to {:?} at {:?}", shortcut.path, to);
-    initialize_com();
-    let path = path_to_c_string(shortcut.path)?;
-    let description = shortcut.description.map(string_to_c_string).transpose()?;
-    let arguments = arguments_to_string(&shortcut.arguments)?;
-    let icon = shortcut.icon.map(path_to_c_string).transpose()?;
-    let show_cmd = if shortcut.show_terminal {
-        SW_SHOW
-    } else {
-        SW_HIDE
+    debug!("Creating Shortcut to {} at {:?}", shortcut.target, to);
+    let _com = ComGuard::new()?;
+    let shell_link = build_shell_link(&shortcut)?;
+    // Written next to the destination and renamed over it so a failed save leaves the old file intact.
+    let temporary = temporary_path(&to);
+    let saved = unsafe {
+        shell_link
+            .cast::<IPersistFile>()
+            .map_err(operation("IPersistFile"))
+            .and_then(|file| {
+                file.Save(PCWSTR(path_to_utf16(&temporary).as_ptr()), TRUE)
+                    .map_err(operation("Save"))
+            })
+    };
+    if let Err(err) = saved {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(err);
+    }
+    // Uses `MoveFileExW` with `MOVEFILE_REPLACE_EXISTING`
+    if let Err(err) = std::fs::rename(&temporary, &to) {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(WindowsShortcutError::IOErr(err));
+    }
+    Ok(())
+}
+/// Creates a Shell Link configured from the shortcut without saving it.
+///
+/// COM must be initialized on the calling thread and stay initialized while the Shell Link is used.
+pub fn build_shell_link(shortcut: &ShortcutFile) -> Result<IShellLinkW, WindowsShortcutError> {
+    let (path, location) = match &shortcut.target {
+        ShortcutTarget::Path(path) => (Some(path_to_utf16(path)), None),
+        ShortcutTarget::Shell(location) => (None, Some(string_to_utf16(location))),
+        target => return Err(WindowsShortcutError::UnsupportedTarget(target.clone())),
     };
-    let working_directory = shortcut
-        .working_directory
-        .map(path_to_c_string)
-        .transpose()?;
-    let to = path_to_utf16(to);
+    let description = shortcut
+        .tooltip
+        .as_deref()
+        .or(shortcut.description.as_deref())
+        .map(string_to_utf16);
+    let arguments = arguments_to_utf16(&shortcut.arguments);
+    let icon = shortcut.icon.as_ref().map(path_to_utf16);
+    let show_cmd = match shortcut.window_state {
+        Some(state) => window_state_to_show_cmd(state),
+        None if shortcut.show_terminal => SW_SHOW,
+        None => SW_HIDE,
+    };
+    let working_directory = shortcut.working_directory.as_ref().map(path_to_utf16);
+    let hotkey = shortcut.hotkey.map(hotkey_to_u16).transpose()?;
     unsafe {
-        let shell_link: IShellLinkA = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
-        shell_link.SetPath(PCSTR(path.as_ptr().cast()))?;
-        shell_link.SetArguments(PCSTR(arguments.as_ptr().cast()))?;
-        shell_link.SetShowCmd(show_cmd)?;
+        let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(operation("CoCreateInstance"))?;
+        if let Some(path) = path {
+            shell_link
+                .SetPath(PCWSTR(path.as_ptr()))
+                .map_err(operation("SetPath"))?;
+        }
+        if let Some(location) = location {
+            set_shell_location(&shell_link, &location)?;
+        }
+        shell_link
+            .SetArguments(PCWSTR(arguments.as_ptr()))
+            .map_err(operation("SetArguments"))?;
+        shell_link
+            .SetShowCmd(show_cmd)
+            .map_err(operation("SetShowCmd"))?;
         if let Some(description) = description {
-            shell_link.SetDescription(PCSTR(description.as_ptr().cast()))?;
+            shell_link
+                .SetDescription(PCWSTR(description.as_ptr()))
+                .map_err(operation("SetDescription"))?;
         }
         if let Some(working_directory) = working_directory {
-            shell_link.SetWorkingDirectory(PCSTR(working_directory.as_ptr().cast()))?;
+            shell_link
+                .SetWorkingDirectory(PCWSTR(working_directory.as_ptr()))
+                .map_err(operation("SetWorkingDirectory"))?;
         }
         if let Some(icon) = icon {
-            shell_link.SetIconLocation(PCSTR(icon.as_ptr().cast()), 0)?;
+            shell_link
+                .SetIconLocation(PCWSTR(icon.as_ptr()), shortcut.icon_index)
+                .map_err(operation("SetIconLocation"))?;
+        }
+        if let Some(hotkey) = hotkey {
+            shell_link
+                .SetHotkey(hotkey)
+                .map_err(operation("SetHotkey"))?;
         }
+        if shortcut.run_as_admin {
+            let data_list = shell_link
+                .cast::<IShellLinkDataList>()
+                .map_err(operation("IShellLinkDataList"))?;
+            let flags = data_list.GetFlags().map_err(operation("GetFlags"))?;
+            data_list
+                .SetFlags(flags | SLDF_RUNAS_USER.0 as u32)
+                .map_err(operation("SetFlags"))?;
+        }
+        Ok(shell_link)
+    }
+}
 
+/// Points the Shell Link at a location in the Shell namespace. Such as `shell:RecycleBinFolder`
+unsafe fn set_shell_location(
+    shell_link: &IShellLinkW,
+    location: &[u16],
+) -> Result<(), WindowsShortcutError> {
+    let mut id_list = std::ptr::null_mut();
+    SHParseDisplayName(
+        PCWSTR(location.as_ptr()),
+        None::<&IBindCtx>,
+        &mut id_list,
+        0,
+        None,
+    )
+    .map_err(operation("SHParseDisplayName"))?;
+    let result = shell_link
+        .SetIDList(id_list)
+        .map_err(operation("SetIDList"));
+    ILFree(Some(id_list));
+    result
+}
+/// Returns the parsing name of the location the Shell Link points to.
+///
+/// Returns `None` if the Shell Link has no location.
+unsafe fn get_shell_location(
+    shell_link: &IShellLinkW,
+) -> Result<Option<String>, WindowsShortcutError> {
+    let id_list = shell_link.GetIDList()?;
+    if id_list.is_null() {
+        return Ok(None);
+    }
+    let name = SHGetNameFromIDList(id_list, SIGDN_DESKTOPABSOLUTEPARSING);
+    ILFree(Some(id_list));
+    let name = name?;
+    let location = String::from_utf16(name.as_wide());
+    CoTaskMemFree(Some(name.0 as *const _));
+    Ok(Some(location?))
+}
+/// Wraps the error of a failed Shell Link call in [WindowsShortcutError::SetOperationFailed]
+fn operation(
+    operation: &'static str,
+) -> impl FnOnce(::windows::core::Error) -> WindowsShortcutError {
+    move |source| WindowsShortcutError::SetOperationFailed { operation, source }
+}
+/// Shell Links are binary so the options have no effect.
+pub fn read_shortcut_file_with_options(
+    path: impl Into<PathBuf>,
+    _options: ParseOptions,
+) -> Result<ShortcutFile, WindowsShortcutError> {
+    read_shortcut_file(path)
+}
+/// Reads a Shortcut from a File. Uses the Win32 API.
+///
+/// The name of the shortcut is taken from the file stem as `.lnk` files do not store one.
+pub fn read_shortcut_file(path: impl Into<PathBuf>) -> Result<ShortcutFile, WindowsShortcutError> {
+    let path = path.into();
+    debug!("Reading Shortcut at {:?}", path);
+    let mut magic = [0u8; 20];
+    match File::open(&path)?.read_exact(&mut magic) {
+        Ok(()) if magic == SHELL_LINK_MAGIC => {}
+        Ok(()) => return Err(WindowsShortcutError::NotAShellLink),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => {
+            return Err(WindowsShortcutError::NotAShellLink)
+        }
+        Err(err) => return Err(err.into()),
+    }
+    let _com = ComGuard::new()?;
+    let name = path
+        .file_stem()
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let from = path_to_utf16(path);
+    let mut path_buffer = [0u16; MAX_PATH as usize];
+    let mut arguments_buffer = [0u16; INFOTIPSIZE as usize];
+    let mut description_buffer = [0u16; INFOTIPSIZE as usize];
+    let mut working_directory_buffer = [0u16; MAX_PATH as usize];
+    let mut icon_buffer = [0u16; MAX_PATH as usize];
+    let mut icon_index = 0;
+    let (show_cmd, hotkey, flags, location) = unsafe {
+        let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
         shell_link
             .cast::<IPersistFile>()?
-            .Save(PCWSTR(to.as_ptr()), TRUE)?;
+            .Load(PCWSTR(from.as_ptr()), STGM_READ)?;
+        shell_link.GetPath(
+            &mut path_buffer,
+            std::ptr::null_mut(),
+            SLGP_RAWPATH.0 as u32,
+        )?;
+        shell_link.GetArguments(&mut arguments_buffer)?;
+        shell_link.GetDescription(&mut description_buffer)?;
+        shell_link.GetWorkingDirectory(&mut working_directory_buffer)?;
+        shell_link.GetIconLocation(&mut icon_buffer, &mut icon_index)?;
+        // Links to locations without a path only have an ID list.
+        let location = if path_buffer[0] == 0 {
+            get_shell_location(&shell_link)?
+        } else {
+            None
+        };
+        (
+            shell_link.GetShowCmd()?,
+            shell_link.GetHotkey()?,
+            shell_link.cast::<IShellLinkDataList>()?.GetFlags()?,
+            location,
+        )
+    };
+    let target = match location {
+        Some(location) => ShortcutTarget::Shell(location),
+        None => ShortcutTarget::Path(buffer_to_path(&path_buffer)),
+    };
+    let arguments = split_arguments(&buffer_to_string(&arguments_buffer)?)?;
+    let shortcut = ShortcutFile {
+        name,
+        description: buffer_to_optional_string(&description_buffer)?,
+        target,
+        arguments,
+        icon: buffer_to_optional_path(&icon_buffer),
+        icon_index,
+        working_directory: buffer_to_optional_path(&working_directory_buffer),
+        show_terminal: show_cmd == SW_SHOW,
+        window_state: show_cmd_to_window_state(show_cmd),
+        categories: vec![],
+        hotkey: u16_to_hotkey(hotkey),
+        run_as_admin: flags & SLDF_RUNAS_USER.0 as u32 != 0,
+        ..Default::default()
+    };
+    Ok(shortcut)
+}
+/// Returns the part of a buffer filled by the Win32 API before the null terminator.
+fn buffer_until_null(buffer: &[u16]) -> &[u16] {
+    let end = buffer.iter().position(|v| *v == 0).unwrap_or(buffer.len());
+    &buffer[..end]
+}
+/// Reads a null terminated string out of a buffer filled by the Win32 API.
+fn buffer_to_string(buffer: &[u16]) -> Result<String, WindowsShortcutError> {
+    String::from_utf16(buffer_until_null(buffer)).map_err(WindowsShortcutError::from)
+}
+/// Same as [buffer_to_string] but returns `None` if the string is empty.
+fn buffer_to_optional_string(buffer: &[u16]) -> Result<Option<String>, WindowsShortcutError> {
+    let value = buffer_to_string(buffer)?;
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(value))
     }
-    Ok(())
 }
-
-pub fn read_shortcut_file(_path: impl Into<PathBuf>) -> Result<ShortcutFile, WindowsShortcutError> {
-    todo!("Support reading shortcuts")
+/// Reads a null terminated path out of a buffer filled by the Win32 API.
+///
+/// Paths do not have to be valid UTF-16 so no conversion is done.
+fn buffer_to_path(buffer: &[u16]) -> PathBuf {
+    PathBuf::from(OsString::from_wide(buffer_until_null(buffer)))
 }
-
-fn arguments_to_string(arguments: &[String]) -> Result<CString, WindowsShortcutError> {
-    let arguments = arguments.join(" ");
-    string_to_c_string(arguments)
+/// Same as [buffer_to_path] but returns `None` if the path is empty.
+fn buffer_to_optional_path(buffer: &[u16]) -> Option<PathBuf> {
+    let path = buffer_until_null(buffer);
+    if path.is_empty() {
+        None
+    } else {
+        Some(buffer_to_path(path))
+    }
 }
 
-fn string_to_c_string(string: impl Into<Vec<u8>>) -> Result<CString, WindowsShortcutError> {
-    CString::new(string).map_err(WindowsShortcutError::from)
+fn window_state_to_show_cmd(state: WindowState) -> SHOW_WINDOW_CMD {
+    match state {
+        WindowState::Normal => SW_SHOWNORMAL,
+        WindowState::Minimized => SW_SHOWMINNOACTIVE,
+        WindowState::Maximized => SW_SHOWMAXIMIZED,
+    }
+}
+/// Returns `None` for show commands that are not a [WindowState] such as `SW_SHOW` and `SW_HIDE`.
+///
+/// Other tools may write `SW_SHOWMINIMIZED` instead of `SW_SHOWMINNOACTIVE` for a minimized window.
+fn show_cmd_to_window_state(show_cmd: SHOW_WINDOW_CMD) -> Option<WindowState> {
+    match show_cmd {
+        SW_SHOWNORMAL => Some(WindowState::Normal),
+        SW_SHOWMINNOACTIVE | SW_SHOWMINIMIZED => Some(WindowState::Minimized),
+        SW_SHOWMAXIMIZED => Some(WindowState::Maximized),
+        _ => None,
+    }
+}
+/// Encodes a hotkey the way `IShellLink::SetHotkey` expects.
+///
+/// The low byte is the virtual key code and the high byte contains the HOTKEYF_* modifier flags.
+fn hotkey_to_u16(hotkey: Hotkey) -> Result<u16, WindowsShortcutError> {
+    if hotkey.modifiers.win {
+        return Err(WindowsShortcutError::UnsupportedHotkey(hotkey));
+    }
+    let key = match hotkey.key {
+        HotkeyKey::Char(c) if c.is_ascii_alphanumeric() => c.to_ascii_uppercase() as u16,
+        HotkeyKey::Function(n @ 1..=24) => VK_F1.0 + (n as u16 - 1),
+        _ => return Err(WindowsShortcutError::UnsupportedHotkey(hotkey)),
+    };
+    let mut modifiers = 0;
+    if hotkey.modifiers.shift {
+        modifiers |= HOTKEYF_SHIFT;
+    }
+    if hotkey.modifiers.ctrl {
+        modifiers |= HOTKEYF_CONTROL;
+    }
+    if hotkey.modifiers.alt {
+        modifiers |= HOTKEYF_ALT;
+    }
+    Ok(key | ((modifiers as u16) << 8))
+}
+/// Decodes a hotkey returned by `IShellLink::GetHotkey`.
+///
+/// Returns `None` if no hotkey is set or the key is not representable by [HotkeyKey]
+fn u16_to_hotkey(value: u16) -> Option<Hotkey> {
+    let [key, modifiers] = value.to_le_bytes();
+    let key = match key as u16 {
+        0 => return None,
+        key @ (0x30..=0x39 | 0x41..=0x5A) => HotkeyKey::Char(key as u8 as char),
+        key if (VK_F1.0..=VK_F24.0).contains(&key) => {
+            HotkeyKey::Function((key - VK_F1.0 + 1) as u8)
+        }
+        _ => return None,
+    };
+    let modifiers = modifiers as u32;
+    Some(Hotkey {
+        modifiers: HotkeyModifiers {
+            ctrl: modifiers & HOTKEYF_CONTROL != 0,
+            alt: modifiers & HOTKEYF_ALT != 0,
+            shift: modifiers & HOTKEYF_SHIFT != 0,
+            win: false,
+        },
+        key,
+    })
+}
+fn arguments_to_utf16(arguments: &[String]) -> Vec<u16> {
+    let arguments: Vec<_> = arguments.iter().map(|v| quote_argument(v)).collect();
+    string_to_utf16(&arguments.join(" "))
+}
+/// Quotes an argument so `CommandLineToArgvW` reads it back unchanged.
+///
+/// Arguments without whitespace or quotes are kept as is.
+/// Backslashes are only doubled in front of a quote or the closing quote.
+fn quote_argument(argument: &str) -> Cow<'_, str> {
+    if !argument.is_empty() && !argument.contains([' ', '\t', '\n', '\x0b', '"']) {
+        return Cow::Borrowed(argument);
+    }
+    let mut quoted = String::with_capacity(argument.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in argument.chars() {
+        let escapes = match c {
+            '\\' => {
+                backslashes += 1;
+                continue;
+            }
+            '"' => backslashes * 2 + 1,
+            _ => backslashes,
+        };
+        quoted.extend(std::iter::repeat_n('\\', escapes));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+/// Splits the arguments of a shortcut with `CommandLineToArgvW`.
+fn split_arguments(arguments: &str) -> Result<Vec<String>, WindowsShortcutError> {
+    if arguments.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    // The first token is parsed as the program name with different rules so a placeholder is used.
+    let command_line = string_to_utf16(&format!("program {}", arguments));
+    let mut count = 0;
+    let argv = unsafe { CommandLineToArgvW(PCWSTR(command_line.as_ptr()), &mut count) };
+    if argv.is_null() {
+        return Err(::windows::core::Error::from_win32().into());
+    }
+    let result = (1..count as usize)
+        .map(|i| unsafe { (*argv.add(i)).to_string() })
+        .collect::<Result<Vec<_>, _>>();
+    unsafe {
+        // LocalFree returns an error when it succeeds because the returned handle is null.
+        let _ = LocalFree(HLOCAL(argv as *mut _));
+    }
+    result.map_err(WindowsShortcutError::from)
+}
+/// Converts a string into a null terminated UTF-16 string.
+fn string_to_utf16(string: &str) -> Vec<u16> {
+    string.encode_utf16().chain(once(0)).collect()
 }
-/// Converts a Path to a CString.
+/// Converts a path into a null terminated UTF-16 string.
 ///
-/// Path must be UTF-8
-fn path_to_c_string(path: impl AsRef<Path>) -> Result<CString, WindowsShortcutError> {
-    let path = path
-        .as_ref()
-        .to_str()
-        .ok_or(WindowsShortcutError::PathToStringError(
-            path.as_ref().as_os_str().to_os_string(),
-        ))?;
-    CString::new(path).map_err(WindowsShortcutError::from)
-}
-fn path_to_utf16(path: PathBuf) -> Vec<u16> {
-    let path = path.into_os_string();
-    return path.encode_wide().chain(once(0)).collect::<Vec<u16>>();
+/// Paths are UTF-16 on Windows so this never fails.
+fn path_to_utf16(path: impl AsRef<Path>) -> Vec<u16> {
+    let path = path.as_ref().as_os_str();
+    path.encode_wide().chain(once(0)).collect::<Vec<u16>>()
+}
+#[cfg(test)]
+mod tests {
+    use super::{
+        hotkey_to_u16, quote_argument, read_shortcut_file, save_shortcut_file,
+        show_cmd_to_window_state, u16_to_hotkey, WindowsShortcutError,
+    };
+    use crate::shortcut_files::{Hotkey, HotkeyKey, ShortcutFile, WindowState};
+
+    #[test]
+    fn test_read_shortcut_file() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("Test", &target)
+            .description("This is a test shortcut")
+            .arg("--test")
+            .arg("-l")
+            .working_directory(target.parent().unwrap())
+            .icon(&target)
+            .icon_index(0);
+        let path = std::env::temp_dir().join("Test.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(shortcut, content);
+    }
+    #[test]
+    fn test_not_a_shell_link() {
+        let path = std::env::temp_dir().join("test_not_a_shell_link.txt");
+        std::fs::write(&path, "Hello World").unwrap();
+        assert!(matches!(
+            read_shortcut_file(path),
+            Err(WindowsShortcutError::NotAShellLink)
+        ));
+    }
+    #[test]
+    fn test_hotkey() {
+        let hotkey = Hotkey::new(HotkeyKey::Char('P')).ctrl().alt();
+        assert_eq!(hotkey_to_u16(hotkey).unwrap(), 0x0650);
+        assert_eq!(u16_to_hotkey(0x0650), Some(hotkey));
+
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestHotkey", &target).hotkey(hotkey);
+        let path = std::env::temp_dir().join("TestHotkey.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(shortcut, content);
+    }
+    #[test]
+    fn test_icon_index() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestIconIndex", &target)
+            .icon("C:\\Windows\\System32\\shell32.dll")
+            .icon_index(3);
+        let path = std::env::temp_dir().join("TestIconIndex.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(content.icon_index, 3);
+        assert_eq!(shortcut, content);
+    }
+    #[test]
+    fn test_run_as_admin() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestRunAsAdmin", &target).run_as_admin();
+        let path = std::env::temp_dir().join("TestRunAsAdmin.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert!(content.run_as_admin);
+        assert_eq!(shortcut, content);
+    }
+    #[test]
+    fn test_window_state() {
+        let target = std::env::current_exe().unwrap();
+        for state in [
+            WindowState::Normal,
+            WindowState::Minimized,
+            WindowState::Maximized,
+        ] {
+            let shortcut = ShortcutFile::new("TestWindowState", &target).window_state(state);
+            let path = std::env::temp_dir().join("TestWindowState.lnk");
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            let content = read_shortcut_file(&path).unwrap();
+            assert_eq!(shortcut, content);
+        }
+    }
+    #[test]
+    fn test_single_threaded_apartment() {
+        use windows::Win32::System::Com::{
+            CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED,
+        };
+        std::thread::spawn(|| {
+            unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }.unwrap();
+            let target = std::env::current_exe().unwrap();
+            let shortcut = ShortcutFile::new("TestSingleThreadedApartment", &target);
+            let path = std::env::temp_dir().join("TestSingleThreadedApartment.lnk");
+            save_shortcut_file(shortcut.clone(), &path).unwrap();
+            assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+            unsafe { CoUninitialize() };
+        })
+        .join()
+        .unwrap();
+    }
+    #[test]
+    fn test_save_operation_failed() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestSaveOperationFailed", &target);
+        let path = std::env::temp_dir()
+            .join("does_not_exist")
+            .join("TestSaveOperationFailed.lnk");
+        let err = save_shortcut_file(shortcut, path).unwrap_err();
+        assert!(matches!(
+            err,
+            WindowsShortcutError::SetOperationFailed {
+                operation: "Save",
+                ..
+            }
+        ));
+        assert!(err.to_string().starts_with("Save failed."));
+    }
+    #[test]
+    fn test_non_ascii_path() {
+        let dir = std::env::temp_dir().join("Tést Ünïcödé");
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("prögram.exe");
+        std::fs::copy(std::env::current_exe().unwrap(), &target).unwrap();
+        let shortcut = ShortcutFile::new("TestNonAsciiPath", &target)
+            .working_directory(&dir)
+            .icon(&target);
+        let path = dir.join("TestNonAsciiPath.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+    }
+    #[test]
+    fn test_non_ascii_description() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestNonAsciiDescription", &target)
+            .description("テスト用のショートカット")
+            .arg("--名前=値");
+        let path = std::env::temp_dir().join("TestNonAsciiDescription.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(
+            content.description.as_deref(),
+            Some("テスト用のショートカット")
+        );
+        assert_eq!(shortcut, content);
+    }
+    #[test]
+    fn test_build_shell_link() {
+        use super::{buffer_to_path, ComGuard, SLGP_RAWPATH};
+        let _com = ComGuard::new().unwrap();
+        let target = std::env::current_exe().unwrap();
+        let shell_link = ShortcutFile::new("TestBuildShellLink", &target)
+            .build_shell_link()
+            .unwrap();
+        let mut path_buffer = [0u16; 260];
+        unsafe {
+            shell_link
+                .GetPath(
+                    &mut path_buffer,
+                    std::ptr::null_mut(),
+                    SLGP_RAWPATH.0 as u32,
+                )
+                .unwrap();
+        }
+        assert_eq!(buffer_to_path(&path_buffer), target);
+    }
+    #[test]
+    fn test_shell_location() {
+        use crate::shortcut_files::ShortcutTarget;
+        let shortcut = ShortcutFile::new_shell("TestShellLocation", "shell:RecycleBinFolder");
+        assert!(shortcut.validate().is_ok());
+        let path = std::env::temp_dir().join("TestShellLocation.lnk");
+        save_shortcut_file(shortcut, &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert!(
+            matches!(content.target, ShortcutTarget::Shell(location) if location.starts_with("::{"))
+        );
+    }
+    #[test]
+    fn test_name_from_file_stem() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("My Program.lnk");
+        save_shortcut_file(ShortcutFile::new("Ignored", &target), &path).unwrap();
+        let shortcut = read_shortcut_file(&path).unwrap();
+        assert_eq!(shortcut.name, "My Program");
+        #[cfg(feature = "desktop")]
+        assert!(shortcut.render().unwrap().contains("Name=My Program\n"));
+    }
+    #[test]
+    fn test_show_cmd_to_window_state() {
+        use windows::Win32::UI::WindowsAndMessaging::{SW_HIDE, SW_SHOW, SW_SHOWMINIMIZED};
+        assert_eq!(
+            show_cmd_to_window_state(SW_SHOWMINIMIZED),
+            Some(WindowState::Minimized)
+        );
+        assert_eq!(show_cmd_to_window_state(SW_SHOW), None);
+        assert_eq!(show_cmd_to_window_state(SW_HIDE), None);
+    }
+    #[test]
+    fn test_maximized_round_trip() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("TestMaximized.lnk");
+        save_shortcut_file(
+            ShortcutFile::new("TestMaximized", &target).window_state(WindowState::Maximized),
+            &path,
+        )
+        .unwrap();
+        let shortcut = read_shortcut_file(&path).unwrap().description("Edited");
+        save_shortcut_file(shortcut, &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(content.window_state, Some(WindowState::Maximized));
+        assert!(!content.show_terminal);
+    }
+    #[test]
+    fn test_tooltip() {
+        let target = std::env::current_exe().unwrap();
+        let path = std::env::temp_dir().join("TestTooltip.lnk");
+        let shortcut = ShortcutFile::new("TestTooltip", &target)
+            .description("Description")
+            .tooltip("Tooltip");
+        save_shortcut_file(shortcut, &path).unwrap();
+        let content = read_shortcut_file(&path).unwrap();
+        assert_eq!(content.description.as_deref(), Some("Tooltip"));
+        assert_eq!(content.tooltip, None);
+    }
+    #[test]
+    fn test_quote_argument() {
+        assert_eq!(quote_argument("--test"), "--test");
+        assert_eq!(quote_argument("C:\\My Files\\"), "\"C:\\My Files\\\\\"");
+        assert_eq!(quote_argument("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_argument(""), "\"\"");
+    }
+    #[test]
+    fn test_arguments_round_trip() {
+        let target = std::env::current_exe().unwrap();
+        let shortcut = ShortcutFile::new("TestArguments", &target)
+            .arg("C:\\My Files\\")
+            .arg("say \"hi\"")
+            .arg("a\\\"b")
+            .arg("")
+            .arg("--plain");
+        let path = std::env::temp_dir().join("TestArguments.lnk");
+        save_shortcut_file(shortcut.clone(), &path).unwrap();
+        assert_eq!(shortcut, read_shortcut_file(&path).unwrap());
+    }
 }